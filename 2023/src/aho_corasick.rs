@@ -0,0 +1,118 @@
+use std::collections::VecDeque;
+
+const NO_STATE: u32 = u32::MAX;
+
+// An Aho–Corasick automaton over bytes, compiled down to a full transition table so that each
+// input byte is a single lookup. Every pattern carries a value that is reported with its matches.
+#[derive(Debug, Clone)]
+pub struct AhoCorasick<V> {
+    transitions: Vec<[u32; 256]>,
+    // Patterns ending in each state as (length, value), including the ones reached through
+    // suffix links, longest first
+    outputs: Vec<Vec<(usize, V)>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Match<V> {
+    pub start: usize,
+    pub end: usize,
+    pub value: V,
+}
+
+impl<V: Copy> AhoCorasick<V> {
+    pub fn new<P: AsRef<[u8]>>(patterns: impl IntoIterator<Item = (P, V)>) -> Self {
+        let mut transitions = vec![[NO_STATE; 256]];
+        let mut outputs = vec![Vec::new()];
+
+        // Build the trie
+        for (pattern, value) in patterns {
+            let pattern = pattern.as_ref();
+            assert!(!pattern.is_empty(), "Patterns can't be empty");
+
+            let mut state = 0;
+            for &byte in pattern {
+                if transitions[state][byte as usize] == NO_STATE {
+                    transitions.push([NO_STATE; 256]);
+                    outputs.push(Vec::new());
+                    transitions[state][byte as usize] = (transitions.len() - 1) as u32;
+                }
+                state = transitions[state][byte as usize] as usize;
+            }
+            outputs[state].push((pattern.len(), value));
+        }
+
+        // Breadth first, so every state's suffix link is finished before the state itself.
+        // Missing edges are filled in with the suffix link's edge, turning the trie into a DFA.
+        let mut suffix_links = vec![0usize; transitions.len()];
+        let mut queue = VecDeque::new();
+        for next in transitions[0].iter_mut() {
+            match *next {
+                NO_STATE => *next = 0,
+                state => queue.push_back(state as usize),
+            }
+        }
+
+        while let Some(state) = queue.pop_front() {
+            let suffix_link = suffix_links[state];
+            let inherited = outputs[suffix_link].clone();
+            outputs[state].extend(inherited);
+
+            let fallbacks = transitions[suffix_link];
+            for (next, fallback) in transitions[state].iter_mut().zip(fallbacks) {
+                match *next {
+                    NO_STATE => *next = fallback,
+                    child => {
+                        suffix_links[child as usize] = fallback as usize;
+                        queue.push_back(child as usize);
+                    }
+                }
+            }
+        }
+
+        Self {
+            transitions,
+            outputs,
+        }
+    }
+
+    // Every occurrence of every pattern, including overlapping ones, in order of where they end
+    pub fn find_overlapping<'a>(&'a self, haystack: &'a [u8]) -> Matches<'a, V> {
+        Matches {
+            automaton: self,
+            haystack,
+            position: 0,
+            state: 0,
+            output: 0,
+        }
+    }
+}
+
+pub struct Matches<'a, V> {
+    automaton: &'a AhoCorasick<V>,
+    haystack: &'a [u8],
+    position: usize,
+    state: usize,
+    output: usize,
+}
+
+impl<V: Copy> Iterator for Matches<'_, V> {
+    type Item = Match<V>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(&(length, value)) = self.automaton.outputs[self.state].get(self.output) {
+                self.output += 1;
+                return Some(Match {
+                    start: self.position - length,
+                    end: self.position,
+                    value,
+                });
+            }
+
+            let &byte = self.haystack.get(self.position)?;
+            self.state = self.automaton.transitions[self.state][byte as usize] as usize;
+            self.position += 1;
+            self.output = 0;
+        }
+    }
+}
@@ -0,0 +1,25 @@
+use std::env;
+
+// Returns the value of `--name value` or `--name=value`, if present
+pub fn value(name: &str) -> Option<String> {
+    let flag = format!("--{name}");
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        if arg == flag {
+            return args.next();
+        }
+        if let Some(value) = arg
+            .strip_prefix(&flag)
+            .and_then(|rest| rest.strip_prefix('='))
+        {
+            return Some(value.to_string());
+        }
+    }
+    None
+}
+
+// Returns whether the bare `--name` switch was passed
+pub fn flag(name: &str) -> bool {
+    let flag = format!("--{name}");
+    env::args().skip(1).any(|arg| arg == flag)
+}
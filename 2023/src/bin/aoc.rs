@@ -0,0 +1,102 @@
+use std::{env, process};
+
+use advent_of_code_2023::{
+    args,
+    generate::{self, Size},
+    harness::{self, Options},
+    rng::Rng,
+};
+
+const YEAR: u32 = 2023;
+
+fn usage() -> ! {
+    eprintln!("Usage: aoc gen <year> <day> [--size N|WxH] [--seed S]");
+    eprintln!("       aoc diff <year> <day> [--random N] [--size N|WxH] [--runs N]");
+    eprintln!();
+    eprintln!("Generators:");
+    for generator in generate::GENERATORS {
+        eprintln!(
+            "  day {:>2}: --size is the {} (default {}x{})",
+            generator.day,
+            generator.size,
+            generator.default_size.width,
+            generator.default_size.height
+        );
+    }
+    eprintln!();
+    eprintln!("Days with alternate implementations to diff:");
+    for day in harness::DAYS {
+        let names: Vec<String> = day
+            .implementations
+            .iter()
+            .map(|implementation| implementation.name())
+            .collect();
+        eprintln!("  day {:>2}: {}", day.day, names.join(", "));
+    }
+    process::exit(2);
+}
+
+fn parse_day(positional: &[String]) -> u32 {
+    let [year, day] = positional else { usage() };
+    let (Ok(year), Ok(day)) = (year.parse::<u32>(), day.parse::<u32>()) else {
+        usage()
+    };
+    if year != YEAR {
+        eprintln!("Only {YEAR} puzzles are handled by this crate");
+        process::exit(1);
+    }
+    day
+}
+
+fn parse_size() -> Option<Size> {
+    args::value("size").map(|size| {
+        size.parse::<Size>().unwrap_or_else(|error| {
+            eprintln!("{error}");
+            process::exit(2);
+        })
+    })
+}
+
+fn gen(positional: &[String]) {
+    let day = parse_day(positional);
+    let Some(generator) = generate::generator(day) else {
+        eprintln!("No generator for day {day}");
+        process::exit(1);
+    };
+
+    let size = parse_size().unwrap_or(generator.default_size);
+    let seed = args::value("seed").map_or(0, |seed| seed.parse().expect("Invalid seed"));
+
+    print!("{}", (generator.generate)(&mut Rng::new(seed), size));
+}
+
+fn diff(positional: &[String]) {
+    let day = parse_day(positional);
+    let Some(day) = harness::day(day) else {
+        eprintln!("Day {day} has only one implementation");
+        process::exit(1);
+    };
+
+    let options = Options {
+        random: args::value("random").map_or(10, |random| random.parse().expect("Invalid count")),
+        size: parse_size(),
+        runs: args::value("runs").map_or(5, |runs| runs.parse().expect("Invalid run count")),
+    };
+    if !harness::diff(day, &options) {
+        process::exit(1);
+    }
+}
+
+fn main() {
+    // Positional arguments come first, options after them
+    let positional: Vec<String> = env::args()
+        .skip(1)
+        .take_while(|arg| !arg.starts_with("--"))
+        .collect();
+
+    match positional.first().map(String::as_str) {
+        Some("gen") => gen(&positional[1..]),
+        Some("diff") => diff(&positional[1..]),
+        _ => usage(),
+    }
+}
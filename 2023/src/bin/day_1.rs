@@ -1,7 +1,7 @@
-use std::fs;
+use advent_of_code_2023::{aho_corasick::Match, checked::CheckedIterator, digit_words, input};
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_1.txt").unwrap();
+    let data = input::read(1);
     let lines = data.split('\n');
     let sum: u32 = lines
         .map(|line| {
@@ -10,65 +10,30 @@ fn part_1() {
                 .filter(|c| c.is_ascii_digit())
                 .map(|d| d.to_digit(10).unwrap())
                 .collect();
-            let first = digits.first().unwrap();
+            let first = digits.first().expect("Line without a digit");
             let last = digits.last().unwrap();
 
             first * 10 + last
         })
-        .sum();
+        .checked_sum();
 
     println!("Part 1: {sum}");
 }
 
 fn part_2() {
-    let data = fs::read_to_string("res/day_1.txt").unwrap();
+    let data = input::read(1);
     let lines = data.split('\n');
+    let matcher = digit_words::matcher();
     let sum: u32 = lines
         .map(|line| {
-            let mut numbers: Vec<u32> = Vec::new();
-            let chars: Vec<char> = line.chars().collect();
-            let mut i = 0;
-            while i < chars.len() {
-                let c = chars[i];
-                match c {
-                    'o' if line.len() > i + 2 && chars[i + 1..i + 3] == ['n', 'e'] => {
-                        numbers.push(1);
-                    }
-                    't' if line.len() > i + 2 && chars[i + 1..i + 3] == ['w', 'o'] => {
-                        numbers.push(2);
-                    }
-                    't' if line.len() > i + 4 && chars[i + 1..i + 5] == ['h', 'r', 'e', 'e'] => {
-                        numbers.push(3);
-                    }
-                    'f' if line.len() > i + 3 && chars[i + 1..i + 4] == ['o', 'u', 'r'] => {
-                        numbers.push(4);
-                    }
-                    'f' if line.len() > i + 3 && chars[i + 1..i + 4] == ['i', 'v', 'e'] => {
-                        numbers.push(5);
-                    }
-                    's' if line.len() > i + 2 && chars[i + 1..i + 3] == ['i', 'x'] => {
-                        numbers.push(6);
-                    }
-                    's' if line.len() > i + 4 && chars[i + 1..i + 5] == ['e', 'v', 'e', 'n'] => {
-                        numbers.push(7);
-                    }
-                    'e' if line.len() > i + 4 && chars[i + 1..i + 5] == ['i', 'g', 'h', 't'] => {
-                        numbers.push(8);
-                    }
-                    'n' if line.len() > i + 3 && chars[i + 1..i + 4] == ['i', 'n', 'e'] => {
-                        numbers.push(9);
-                    }
-                    c if c.is_ascii_digit() => numbers.push(c.to_digit(10).unwrap()),
-                    _ => {}
-                }
-                i += 1;
-            }
+            let numbers: Vec<Match<u32>> = matcher.find_overlapping(line.as_bytes()).collect();
 
-            let first = numbers.first().unwrap();
-            let last = numbers.last().unwrap();
-            first * 10 + last
+            // Matches come out in order of where they end, so pick by where they start
+            let first = numbers.iter().min_by_key(|number| number.start).unwrap();
+            let last = numbers.iter().max_by_key(|number| number.start).unwrap();
+            first.value * 10 + last.value
         })
-        .sum();
+        .checked_sum();
 
     println!("Part 2: {sum}");
 }
@@ -1,4 +1,9 @@
-use std::{collections::BTreeSet, fs};
+use std::{collections::BTreeSet, process};
+
+use advent_of_code_2023::{
+    args, image, input,
+    render::{self, Colour, Renderer, Style},
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Pipe {
@@ -22,91 +27,154 @@ enum Direction {
 
 type Grid = Vec<Vec<Pipe>>;
 type Position = (usize, usize);
-type Vector = (Position, Direction);
 
-#[allow(dead_code)]
-fn print_grid(
-    grid: &Grid,
-    highlighted_pipes: Option<&BTreeSet<Position>>,
-    inside_pipes: Option<&Vec<Position>>,
-) {
-    for (y, row) in grid.iter().enumerate() {
-        for (x, col) in row.iter().enumerate() {
-            let mut is_highlighted = false;
-            if let Some(highlighted_pipes) = highlighted_pipes {
-                if highlighted_pipes.contains(&(x, y)) {
-                    is_highlighted = true;
-                }
-            }
-            let mut is_inside = false;
-            if let Some(inside_pipes) = inside_pipes {
-                if inside_pipes.contains(&(x, y)) {
-                    is_inside = true;
-                }
-            }
-            let mut highlight_start = "";
-            if is_highlighted {
-                highlight_start = "\x1b[0;31m";
-            }
-            if is_inside {
-                highlight_start = "\x1b[0;34m";
-            }
-            let c = match *col {
-                Pipe::NorthSouth => '┃',
-                Pipe::EastWest => '━',
-                Pipe::NorthEast => '┗',
-                Pipe::NorthWest => '┛',
-                Pipe::SouthWest => '┓',
-                Pipe::SouthEast => '┏',
-                Pipe::Start => 'S',
-                Pipe::Ground => '.',
-            };
-            let mut highlight_end = "";
-            if is_highlighted || is_inside {
-                highlight_end = "\x1b[0m";
-            }
-            print!("{}{}{}", highlight_start, c, highlight_end);
+fn draw_grid(grid: &Grid, loop_pipes: &BTreeSet<Position>, inside_pipes: &BTreeSet<Position>) {
+    Renderer::new(|pipe: &Pipe| match pipe {
+        Pipe::NorthSouth => '┃',
+        Pipe::EastWest => '━',
+        Pipe::NorthEast => '┗',
+        Pipe::NorthWest => '┛',
+        Pipe::SouthWest => '┓',
+        Pipe::SouthEast => '┏',
+        Pipe::Start => 'S',
+        Pipe::Ground => '.',
+    })
+    .highlight(Style::foreground(Colour::Red), |x, y| {
+        loop_pipes.contains(&(x, y))
+    })
+    .highlight(Style::foreground(Colour::Blue), |x, y| {
+        inside_pipes.contains(&(x, y))
+    })
+    .print(grid);
+}
+
+impl Direction {
+    const ALL: [Direction; 4] = [
+        Direction::North,
+        Direction::South,
+        Direction::East,
+        Direction::West,
+    ];
+
+    fn opposite(self) -> Self {
+        match self {
+            Direction::North => Direction::South,
+            Direction::South => Direction::North,
+            Direction::East => Direction::West,
+            Direction::West => Direction::East,
         }
-        println!();
     }
 }
 
-fn next_pipe(grid: &Grid, vector: Vector) -> Vector {
-    let pipe_segment = &grid[vector.0 .1][vector.0 .0];
-    match vector.1 {
-        Direction::North => match pipe_segment {
-            Pipe::NorthSouth => ((vector.0 .0, vector.0 .1 + 1), Direction::North),
-            Pipe::NorthWest => ((vector.0 .0 - 1, vector.0 .1), Direction::East),
-            Pipe::NorthEast => ((vector.0 .0 + 1, vector.0 .1), Direction::West),
-            _ => panic!("Invalid north pipe connection at {:?}", vector.0),
-        },
-        Direction::South => match pipe_segment {
-            Pipe::NorthSouth => ((vector.0 .0, vector.0 .1 - 1), Direction::South),
-            Pipe::SouthWest => ((vector.0 .0 - 1, vector.0 .1), Direction::East),
-            Pipe::SouthEast => ((vector.0 .0 + 1, vector.0 .1), Direction::West),
-            _ => panic!("Invalid south pipe connection at {:?}", vector.0),
-        },
-        Direction::East => match pipe_segment {
-            Pipe::EastWest => ((vector.0 .0 - 1, vector.0 .1), Direction::East),
-            Pipe::NorthEast => ((vector.0 .0, vector.0 .1 - 1), Direction::South),
-            Pipe::SouthEast => ((vector.0 .0, vector.0 .1 + 1), Direction::North),
-            _ => panic!("Invalid east pipe connection at {:?}", vector.0),
-        },
-        Direction::West => match pipe_segment {
-            Pipe::EastWest => ((vector.0 .0 + 1, vector.0 .1), Direction::West),
-            Pipe::NorthWest => ((vector.0 .0, vector.0 .1 - 1), Direction::South),
-            Pipe::SouthWest => ((vector.0 .0, vector.0 .1 + 1), Direction::North),
-            _ => panic!("Invalid west pipe connection at {:?}", vector.0),
-        },
+impl Pipe {
+    // The two sides a pipe opens onto
+    fn connections(self) -> &'static [Direction] {
+        match self {
+            Pipe::NorthSouth => &[Direction::North, Direction::South],
+            Pipe::EastWest => &[Direction::East, Direction::West],
+            Pipe::NorthEast => &[Direction::North, Direction::East],
+            Pipe::NorthWest => &[Direction::North, Direction::West],
+            Pipe::SouthWest => &[Direction::South, Direction::West],
+            Pipe::SouthEast => &[Direction::South, Direction::East],
+            Pipe::Start | Pipe::Ground => &[],
+        }
+    }
+
+    fn connects(self, direction: Direction) -> bool {
+        self.connections().contains(&direction)
+    }
+
+    fn from_connections(a: Direction, b: Direction) -> Self {
+        [
+            Pipe::NorthSouth,
+            Pipe::EastWest,
+            Pipe::NorthEast,
+            Pipe::NorthWest,
+            Pipe::SouthWest,
+            Pipe::SouthEast,
+        ]
+        .into_iter()
+        .find(|pipe| pipe.connects(a) && pipe.connects(b))
+        .unwrap()
+    }
+
+    fn symbol(self) -> char {
+        match self {
+            Pipe::NorthSouth => '|',
+            Pipe::EastWest => '-',
+            Pipe::NorthEast => 'L',
+            Pipe::NorthWest => 'J',
+            Pipe::SouthWest => '7',
+            Pipe::SouthEast => 'F',
+            Pipe::Start => 'S',
+            Pipe::Ground => '.',
+        }
     }
 }
 
-fn main() {
-    let data = fs::read_to_string("res/day_10.txt").unwrap();
-    let lines = data.split('\n');
+// The neighbouring position in `direction`, if it's on the grid
+fn neighbour(grid: &Grid, (x, y): Position, direction: Direction) -> Option<Position> {
+    let (x, y) = match direction {
+        Direction::North => (x, y.checked_sub(1)?),
+        Direction::South => (x, y + 1),
+        Direction::East => (x + 1, y),
+        Direction::West => (x.checked_sub(1)?, y),
+    };
+    (y < grid.len() && x < grid[y].len()).then_some((x, y))
+}
+
+// Follows the loop from `start`, leaving towards `direction`, and returns every position on it in
+// order. Fails with the coordinates of the first pipe that doesn't connect onwards.
+fn follow_loop(
+    grid: &Grid,
+    start: Position,
+    direction: Direction,
+) -> Result<Vec<Position>, String> {
+    let mut path = vec![start];
+    let mut position = start;
+    let mut direction = direction;
+    loop {
+        let pipe = grid[position.1][position.0];
+        let Some(next) = neighbour(grid, position, direction) else {
+            return Err(format!(
+                "the loop leaves the map from '{}' at {position:?}",
+                pipe.symbol()
+            ));
+        };
+        if next == start {
+            return Ok(path);
+        }
+
+        let next_pipe = grid[next.1][next.0];
+        let arriving = direction.opposite();
+        if !next_pipe.connects(arriving) {
+            return Err(format!(
+                "the loop breaks at {next:?}: '{}' doesn't connect back to '{}' at {position:?}",
+                next_pipe.symbol(),
+                pipe.symbol()
+            ));
+        }
+        if path.len() > grid.len() * grid[0].len() {
+            return Err(format!("the loop from {start:?} never closes"));
+        }
+
+        path.push(next);
+        position = next;
+        direction = *next_pipe
+            .connections()
+            .iter()
+            .find(|side| **side != arriving)
+            .unwrap();
+    }
+}
 
-    let mut starting_position = (0, 0);
-    let grid: Grid = lines
+fn parse_grid(data: &str) -> Result<(Grid, Position), String> {
+    let mut errors = Vec::new();
+    let mut starts = Vec::new();
+    // Trailing blank lines aren't rows of the map
+    let grid: Grid = data
+        .trim_end_matches('\n')
+        .split('\n')
         .enumerate()
         .map(|(y, line)| {
             line.chars()
@@ -119,145 +187,285 @@ fn main() {
                     '7' => Pipe::SouthWest,
                     'F' => Pipe::SouthEast,
                     'S' => {
-                        starting_position = (x, y);
+                        starts.push((x, y));
                         Pipe::Start
                     }
                     '.' => Pipe::Ground,
-                    _ => panic!("Invalid pipe segment"),
+                    _ => {
+                        errors.push(format!("invalid tile '{char}' at {:?}", (x, y)));
+                        Pipe::Ground
+                    }
                 })
                 .collect()
         })
         .collect();
-    let starting_position = starting_position;
-
-    let mut pipe_1: Option<(Position, Direction)> = None;
-    let mut pipe_2: Option<(Position, Direction)> = None;
-    if starting_position.1 > 0 {
-        let north_pipe = &grid[starting_position.1 - 1][starting_position.0];
-        if north_pipe == &Pipe::NorthSouth
-            || north_pipe == &Pipe::SouthEast
-            || north_pipe == &Pipe::SouthWest
-        {
-            pipe_1 = Some((
-                (starting_position.0, starting_position.1 - 1),
-                Direction::South,
-            ));
-        }
+
+    if let Some((y, row)) = grid
+        .iter()
+        .enumerate()
+        .find(|(_, row)| row.len() != grid[0].len())
+    {
+        errors.push(format!(
+            "row {y} has {} tiles where row 0 has {}",
+            row.len(),
+            grid[0].len()
+        ));
     }
-    if starting_position.1 + 1 < grid.len() {
-        let south_pipe = &grid[starting_position.1 + 1][starting_position.0];
-        if south_pipe == &Pipe::NorthSouth
-            || south_pipe == &Pipe::NorthEast
-            || south_pipe == &Pipe::NorthWest
-        {
-            if pipe_1.is_none() {
-                pipe_1 = Some((
-                    (starting_position.0, starting_position.1 + 1),
-                    Direction::North,
-                ));
-            } else {
-                pipe_2 = Some((
-                    (starting_position.0, starting_position.1 + 1),
-                    Direction::North,
-                ));
-            }
-        }
+    match starts[..] {
+        [] => errors.push(String::from("there is no start tile")),
+        [_] => {}
+        _ => errors.push(format!("there are several start tiles at {starts:?}")),
     }
-    if starting_position.0 > 0 {
-        let east_pipe = &grid[starting_position.1][starting_position.0 - 1];
-        if east_pipe == &Pipe::EastWest
-            || east_pipe == &Pipe::NorthEast
-            || east_pipe == &Pipe::SouthEast
-        {
-            if pipe_1.is_none() {
-                pipe_1 = Some((
-                    (starting_position.0 - 1, starting_position.1),
-                    Direction::East,
-                ));
-            } else {
-                pipe_2 = Some((
-                    (starting_position.0 - 1, starting_position.1),
-                    Direction::East,
-                ));
-            }
-        }
+
+    if errors.is_empty() {
+        Ok((grid, starts[0]))
+    } else {
+        Err(errors.join("\n"))
     }
-    if starting_position.0 + 1 < grid[starting_position.1].len() {
-        let west_pipe = &grid[starting_position.1][starting_position.0 + 1];
-        if west_pipe == &Pipe::EastWest
-            || west_pipe == &Pipe::NorthWest
-            || west_pipe == &Pipe::SouthWest
-        {
-            if pipe_1.is_none() {
-                pipe_1 = Some((
-                    (starting_position.0 + 1, starting_position.1),
-                    Direction::West,
-                ));
-            } else {
-                pipe_2 = Some((
-                    (starting_position.0 + 1, starting_position.1),
-                    Direction::West,
-                ));
+}
+
+// Works out which pipe the start tile hides from the neighbours that connect to it. When more
+// than two neighbours do, only the pairs that lead around a closed loop count, and there must be
+// exactly one of those.
+fn infer_start(grid: &Grid, start: Position) -> Result<(Pipe, Vec<Position>), String> {
+    let connected: Vec<Direction> = Direction::ALL
+        .into_iter()
+        .filter(|direction| {
+            neighbour(grid, start, *direction)
+                .is_some_and(|(x, y)| grid[y][x].connects(direction.opposite()))
+        })
+        .collect();
+    if connected.len() < 2 {
+        return Err(format!(
+            "the start at {start:?} only has {} connecting neighbour{}",
+            connected.len(),
+            if connected.len() == 1 { "" } else { "s" }
+        ));
+    }
+
+    let mut candidates = Vec::new();
+    let mut failures = Vec::new();
+    for (i, &first) in connected.iter().enumerate() {
+        for &second in &connected[i + 1..] {
+            let pipe = Pipe::from_connections(first, second);
+            match follow_loop(grid, start, first) {
+                Ok(path) => {
+                    // The loop has to come back into the start from the second side
+                    let last = *path.last().unwrap();
+                    if neighbour(grid, start, second) == Some(last) && path.len() > 2 {
+                        candidates.push((pipe, path));
+                    } else {
+                        failures.push(format!(
+                            "as '{}' the loop doesn't return through the start's other side",
+                            pipe.symbol()
+                        ));
+                    }
+                }
+                Err(error) => failures.push(format!("as '{}' {error}", pipe.symbol())),
             }
         }
     }
 
-    let mut pipe_1 = pipe_1.unwrap();
-    let mut pipe_2 = pipe_2.unwrap();
-
-    let mut steps: usize = 1;
-
-    let mut pipes = BTreeSet::new();
-    pipes.insert(starting_position);
-    pipes.insert(pipe_1.0);
-    pipes.insert(pipe_2.0);
-
-    loop {
-        let next_pipe_1 = next_pipe(&grid, pipe_1);
-        let next_pipe_2 = next_pipe(&grid, pipe_2);
-
-        steps += 1;
-
-        pipes.insert(next_pipe_1.0);
-        pipes.insert(next_pipe_2.0);
-
-        if next_pipe_1.0 .0 == next_pipe_2.0 .0 && next_pipe_1.0 .1 == next_pipe_2.0 .1 {
-            break;
+    match candidates.len() {
+        1 => Ok(candidates.pop().unwrap()),
+        0 => Err(format!(
+            "no loop closes through the start at {start:?}:\n  {}",
+            failures.join("\n  ")
+        )),
+        _ => {
+            let shapes: Vec<String> = candidates
+                .iter()
+                .map(|(pipe, _)| format!("'{}'", pipe.symbol()))
+                .collect();
+            Err(format!(
+                "the start at {start:?} is ambiguous, it could be any of {}",
+                shapes.join(", ")
+            ))
         }
-
-        pipe_1 = next_pipe_1;
-        pipe_2 = next_pipe_2;
     }
+}
 
-    let mut inside_pipes: Vec<Position> = Vec::new();
-    for (y, _) in grid.iter().enumerate() {
+// Walks each row keeping track of whether it's inside the loop, which flips on crossing a `|`
+// or a pair of corners that together cross the row (`L7` or `FJ`, but not `LJ` or `F7`)
+fn scanline(grid: &Grid, pipes: &BTreeSet<Position>) -> BTreeSet<Position> {
+    let mut inside_pipes: BTreeSet<Position> = BTreeSet::new();
+    for (y, row) in grid.iter().enumerate() {
         let mut inside = false;
         let mut consuming_pipe = None;
-        for x in 0..grid[y].len() {
+        for (x, &pipe) in row.iter().enumerate() {
             if pipes.contains(&(x, y)) {
-                if grid[y][x] == Pipe::NorthSouth {
+                if pipe == Pipe::NorthSouth {
                     inside = !inside;
-                } else if grid[y][x] == Pipe::NorthEast || grid[y][x] == Pipe::SouthEast {
-                    consuming_pipe = Some(grid[y][x]);
-                } else if grid[y][x] == Pipe::NorthWest || grid[y][x] == Pipe::SouthWest {
+                } else if pipe == Pipe::NorthEast || pipe == Pipe::SouthEast {
+                    consuming_pipe = Some(pipe);
+                } else if pipe == Pipe::NorthWest || pipe == Pipe::SouthWest {
                     if let Some(Pipe::NorthEast) = consuming_pipe {
-                        if grid[y][x] == Pipe::SouthWest {
+                        if pipe == Pipe::SouthWest {
                             inside = !inside;
                             consuming_pipe = None;
                         }
                     } else if let Some(Pipe::SouthEast) = consuming_pipe {
-                        if grid[y][x] == Pipe::NorthWest {
+                        if pipe == Pipe::NorthWest {
                             inside = !inside;
                             consuming_pipe = None;
                         }
                     }
                 }
             } else if inside {
-                inside_pipes.push((x, y));
+                inside_pipes.insert((x, y));
             }
         }
     }
+    inside_pipes
+}
+
+// The shoelace formula gives the area of the polygon through the centres of the loop's tiles,
+// and Pick's theorem (area = inside + boundary / 2 - 1) turns that into the number of tiles
+// strictly inside it
+fn shoelace(path: &[Position]) -> usize {
+    let twice_area: i64 = path
+        .iter()
+        .zip(path.iter().cycle().skip(1))
+        .map(|(&(x1, y1), &(x2, y2))| x1 as i64 * y2 as i64 - x2 as i64 * y1 as i64)
+        .sum();
+    let area = twice_area.unsigned_abs() as usize / 2;
+    area + 1 - path.len() / 2
+}
+
+// Draws each loop tile as a 3x3 block so the gaps between neighbouring pipes become real
+// cells, then floods in from the border. Tiles whose centre the flood never reaches are inside.
+fn flood_fill(grid: &Grid, pipes: &BTreeSet<Position>) -> BTreeSet<Position> {
+    let height = grid.len() * 3;
+    let width = grid[0].len() * 3;
+    let mut blocked = vec![vec![false; width]; height];
+    for &(x, y) in pipes {
+        let (centre_x, centre_y) = (x * 3 + 1, y * 3 + 1);
+        blocked[centre_y][centre_x] = true;
+        for direction in grid[y][x].connections() {
+            let (x, y) = match direction {
+                Direction::North => (centre_x, centre_y - 1),
+                Direction::South => (centre_x, centre_y + 1),
+                Direction::East => (centre_x + 1, centre_y),
+                Direction::West => (centre_x - 1, centre_y),
+            };
+            blocked[y][x] = true;
+        }
+    }
+
+    let mut outside = vec![vec![false; width]; height];
+    let mut queue: Vec<Position> = (0..width)
+        .flat_map(|x| [(x, 0), (x, height - 1)])
+        .chain((0..height).flat_map(|y| [(0, y), (width - 1, y)]))
+        .collect();
+    while let Some((x, y)) = queue.pop() {
+        if blocked[y][x] || outside[y][x] {
+            continue;
+        }
+        outside[y][x] = true;
+        if x > 0 {
+            queue.push((x - 1, y));
+        }
+        if x + 1 < width {
+            queue.push((x + 1, y));
+        }
+        if y > 0 {
+            queue.push((x, y - 1));
+        }
+        if y + 1 < height {
+            queue.push((x, y + 1));
+        }
+    }
+
+    (0..grid.len())
+        .flat_map(|y| (0..grid[0].len()).map(move |x| (x, y)))
+        .filter(|&(x, y)| !pipes.contains(&(x, y)) && !outside[y * 3 + 1][x * 3 + 1])
+        .collect()
+}
+
+fn fail(error: &str) -> ! {
+    eprintln!("{error}");
+    process::exit(1);
+}
+
+fn main() {
+    let data = input::read(10);
+    let (mut grid, starting_position) =
+        parse_grid(&data).unwrap_or_else(|error| fail(&format!("Invalid map: {error}")));
+    let (start_pipe, path) = infer_start(&grid, starting_position)
+        .unwrap_or_else(|error| fail(&format!("Invalid map: {error}")));
+    grid[starting_position.1][starting_position.0] = start_pipe;
+    let grid = grid;
+
+    // Every loop on a square grid has an even length, and the farthest point is halfway round
+    let steps = path.len() / 2;
+    let pipes: BTreeSet<Position> = path.iter().copied().collect();
+
+    // `--area` picks how part 2 is counted. Shoelace only gives a count, so the tiles shown by
+    // `--visualize` and `--render` then come from the scanline.
+    let algorithm = args::value("area").unwrap_or_else(|| String::from("scanline"));
+    let inside_pipes = match algorithm.as_str() {
+        "flood-fill" => flood_fill(&grid, &pipes),
+        "scanline" | "shoelace" => scanline(&grid, &pipes),
+        _ => fail(&format!("Unknown area algorithm {algorithm}")),
+    };
+    let inside_count = if algorithm == "shoelace" {
+        shoelace(&path)
+    } else {
+        inside_pipes.len()
+    };
+
+    if render::enabled() {
+        draw_grid(&grid, &pipes, &inside_pipes);
+    }
+
+    if let Some(path) = image::render_path() {
+        image::write_grid(&path, &grid, |x, y, pipe| {
+            if pipes.contains(&(x, y)) {
+                image::RED
+            } else if inside_pipes.contains(&(x, y)) {
+                image::BLUE
+            } else if *pipe == Pipe::Ground {
+                image::WHITE
+            } else {
+                image::GREY
+            }
+        })
+        .unwrap_or_else(|error| image::fail(&path, error));
+    }
 
     println!("Part 1: {steps}");
-    println!("Part 2: {}", inside_pipes.len());
+    println!("Part 2: {inside_count}");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // The enclosed tile count from each of the three algorithms, in the order scanline, shoelace
+    // and flood fill
+    fn areas(data: &str) -> [usize; 3] {
+        let (mut grid, start) = parse_grid(data).unwrap();
+        let (start_pipe, path) = infer_start(&grid, start).unwrap();
+        grid[start.1][start.0] = start_pipe;
+        let pipes: BTreeSet<Position> = path.iter().copied().collect();
+
+        [
+            scanline(&grid, &pipes).len(),
+            shoelace(&path),
+            flood_fill(&grid, &pipes).len(),
+        ]
+    }
+
+    #[test]
+    fn algorithms_agree_on_example() {
+        assert_eq!(areas(include_str!("../../res/examples/day_10.txt")), [4; 3]);
+    }
+
+    #[test]
+    fn algorithms_agree_on_junk_example() {
+        assert_eq!(
+            areas(include_str!("../../res/examples/day_10_junk.txt")),
+            [10; 3]
+        );
+    }
 }
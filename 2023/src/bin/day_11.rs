@@ -1,32 +1,53 @@
-use std::fs;
+use advent_of_code_2023::{args, checked, input};
+
+// Where each galaxy ends up along one axis once every empty line before it counts `factor` times.
+// `empty` says which lines along the axis hold no galaxies.
+fn expanded_positions(positions: &[usize], empty: &[bool], factor: u128) -> Vec<u128> {
+    // empty_before[i] is the number of empty lines before line i
+    let mut empty_before = Vec::with_capacity(empty.len());
+    let mut count: u128 = 0;
+    for is_empty in empty {
+        empty_before.push(count);
+        count += *is_empty as u128;
+    }
+
+    positions
+        .iter()
+        .map(|&position| {
+            checked::add(
+                position as u128,
+                checked::mul(empty_before[position], factor - 1),
+            )
+        })
+        .collect()
+}
+
+// The sum of |a - b| over every pair. Once sorted, the i-th position is larger than the i before
+// it, so it contributes `position * i` minus the sum of those earlier positions.
+fn pairwise_distance(mut positions: Vec<u128>) -> u128 {
+    positions.sort_unstable();
+    let mut total: u128 = 0;
+    let mut prefix: u128 = 0;
+    for (i, position) in positions.into_iter().enumerate() {
+        total = checked::add(total, checked::mul(position, i as u128) - prefix);
+        prefix = checked::add(prefix, position);
+    }
+    total
+}
 
 fn main() {
-    let data = fs::read_to_string("res/day_11.txt").unwrap();
+    let data = input::read(11);
     let grid: Vec<Vec<bool>> = data
         .split('\n')
         .map(|line| line.chars().map(|char| char == '#').collect::<Vec<bool>>())
         .collect();
 
-    let grid_height = grid.len();
     let grid_width = grid[0].len();
 
-    let mut empty_rows = vec![false; grid_height];
-    for (i, row) in grid.iter().enumerate() {
-        if row.iter().all(|v| !*v) {
-            empty_rows[i] = true;
-        }
-    }
-
-    let mut empty_columns = vec![false; grid_height];
-    'outer: for x in 0..grid_width {
-        for y in 0..grid_height {
-            if grid[y][x] {
-                continue 'outer;
-            }
-        }
-
-        empty_columns[x] = true;
-    }
+    let empty_rows: Vec<bool> = grid.iter().map(|row| row.iter().all(|v| !*v)).collect();
+    let empty_columns: Vec<bool> = (0..grid_width)
+        .map(|x| grid.iter().all(|row| !row[x]))
+        .collect();
 
     let galaxies: Vec<(usize, usize)> = grid
         .into_iter()
@@ -38,38 +59,23 @@ fn main() {
                 .map(move |(x, _)| (x, y))
         })
         .collect();
+    let xs: Vec<usize> = galaxies.iter().map(|(x, _)| *x).collect();
+    let ys: Vec<usize> = galaxies.iter().map(|(_, y)| *y).collect();
 
-    let mut accumulator_1: usize = 0;
-    let mut accumulator_2: usize = 0;
-    for i in 0..galaxies.len() - 1 {
-        for j in i + 1..galaxies.len() {
-            let galaxy_1 = galaxies[i];
-            let galaxy_2 = galaxies[j];
-
-            let start_x = galaxy_1.0.min(galaxy_2.0);
-            let start_y = galaxy_1.1.min(galaxy_2.1);
+    // Manhattan distances split into independent sums along each axis
+    let total_distance = |factor: u128| {
+        assert!(factor > 0, "The expansion factor must be at least 1");
+        checked::add(
+            pairwise_distance(expanded_positions(&xs, &empty_columns, factor)),
+            pairwise_distance(expanded_positions(&ys, &empty_rows, factor)),
+        )
+    };
 
-            let x_diff = (galaxy_1.0 as isize - galaxy_2.0 as isize).unsigned_abs();
-            let y_diff = (galaxy_1.1 as isize - galaxy_2.1 as isize).unsigned_abs();
+    println!("Part 1: {}", total_distance(2));
+    println!("Part 2: {}", total_distance(1_000_000));
 
-            let x_expanses: usize = empty_columns[start_x..start_x + x_diff]
-                .iter()
-                .filter(|empty| **empty)
-                .count();
-            let y_expanses: usize = empty_rows[start_y..start_y + y_diff]
-                .iter()
-                .filter(|empty| **empty)
-                .count();
-
-            accumulator_1 +=
-                (x_diff - x_expanses) + (y_diff - y_expanses) + (y_expanses * 2) + (x_expanses * 2);
-            accumulator_2 += (x_diff - x_expanses)
-                + (y_diff - y_expanses)
-                + (y_expanses * 1_000_000)
-                + (x_expanses * 1_000_000);
-        }
+    if let Some(factor) = args::value("factor") {
+        let factor: u128 = factor.parse().expect("Invalid expansion factor");
+        println!("Factor {factor}: {}", total_distance(factor));
     }
-
-    println!("Part 1: {accumulator_1}");
-    println!("Part 2: {accumulator_2}");
 }
@@ -1,6 +1,6 @@
-use std::fs;
+use std::{fmt, fs};
 
-use cached::proc_macro::cached;
+use advent_of_code_2023::{args, checked, input, rng::Rng};
 
 #[derive(Debug, Clone, PartialEq, Eq, Hash)]
 struct Row {
@@ -8,14 +8,13 @@ struct Row {
     condition_counts: Vec<usize>,
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum SpringCondition {
     Working,
     Damaged,
     Unknown,
 }
 
-#[allow(dead_code)]
 fn conditions_to_str(input: &[SpringCondition]) -> String {
     String::from_utf8(
         input
@@ -30,232 +29,647 @@ fn conditions_to_str(input: &[SpringCondition]) -> String {
     .unwrap()
 }
 
-#[cached]
-fn check(
-    conditions: Vec<SpringCondition>,
-    condition_counts: Vec<usize>,
-    working_count: usize,
-    damaged_count: usize,
-) -> usize {
-    // If we run the function on empty values, we have found a match
-    if conditions.is_empty()
-        && condition_counts.is_empty()
-        && working_count == 0
-        && damaged_count == 0
-    {
-        return 1;
-    }
-
-    let mut sum = 0;
-
-    match conditions[0] {
-        SpringCondition::Working => {
-            // If it's a working spring, we can just ignore it and move on
-            return check(
-                conditions[1..].to_vec(),
-                condition_counts,
-                working_count,
-                damaged_count,
-            );
+fn parse_conditions(conditions: &str) -> Vec<SpringCondition> {
+    conditions
+        .chars()
+        .map(|char| match char {
+            '.' => SpringCondition::Working,
+            '#' => SpringCondition::Damaged,
+            '?' => SpringCondition::Unknown,
+            _ => panic!("Invalid spring condition"),
+        })
+        .collect()
+}
+
+fn parse_condition_counts(condition_counts: &str) -> Vec<usize> {
+    condition_counts
+        .split(',')
+        .filter(|count| !count.is_empty())
+        .map(|count| count.parse().unwrap())
+        .collect()
+}
+
+impl Row {
+    // A row is its conditions followed by the damaged group sizes, which may be left out when
+    // there are none
+    fn parse(line: &str) -> Self {
+        let (conditions, condition_counts) = line.split_once(' ').unwrap_or((line, ""));
+        Self {
+            conditions: parse_conditions(conditions),
+            condition_counts: parse_condition_counts(condition_counts),
         }
-        SpringCondition::Damaged => {
-            let expected_contiguous_damaged_springs = condition_counts[0];
+    }
 
-            // Check if we have at least as many springs available to check as expected
-            if conditions.len() < expected_contiguous_damaged_springs {
-                // Not enough contiguous springs, not a match
-                return 0;
-            }
+    // `factor` copies of the row, with the conditions joined by unknown springs
+    fn unfold(&self, factor: usize) -> Self {
+        assert!(factor > 0, "The unfold factor must be at least 1");
+        let mut conditions = self.conditions.clone();
+        for _ in 1..factor {
+            conditions.push(SpringCondition::Unknown);
+            conditions.extend_from_slice(&self.conditions);
+        }
+
+        Self {
+            conditions,
+            condition_counts: self.condition_counts.repeat(factor),
+        }
+    }
+
+    fn arrangements(&self) -> u128 {
+        let mut states = States::new(&self.condition_counts);
+        for condition in &self.conditions {
+            states = states.step(*condition);
+        }
+        states.accepted()
+    }
+
+    // The states after each prefix of the conditions, from the empty prefix to the whole row
+    fn prefixes(&self) -> Vec<States<'_>> {
+        let mut prefixes = vec![States::new(&self.condition_counts)];
+        for condition in &self.conditions {
+            prefixes.push(prefixes.last().unwrap().step(*condition));
+        }
+        prefixes
+    }
 
-            let mut consumed_unknown_damaged = 0;
-            for condition in conditions
-                .iter()
-                .take(expected_contiguous_damaged_springs)
-                .skip(1)
+    // completions[i] counts, for every state, the ways to fill in the conditions from position i
+    // onwards so that the row ends up matching its groups
+    fn completions(&self) -> Vec<States<'_>> {
+        let mut completions = vec![States::accepting(&self.condition_counts)];
+        for condition in self.conditions.iter().rev() {
+            completions.push(completions.last().unwrap().step_back(*condition));
+        }
+        completions.reverse();
+        completions
+    }
+
+    // Every concrete arrangement, working springs before damaged ones at each unknown. Only
+    // states that can still be completed are explored, so each step towards the next arrangement
+    // is useful.
+    fn iter(&self) -> Arrangements<'_> {
+        let completions = self.completions();
+        let mut arrangements = Arrangements {
+            row: self,
+            filled: Vec::with_capacity(self.conditions.len()),
+            stack: Vec::new(),
+            empty: self.conditions.is_empty() && completions[0].get(0, 0) > 0,
+            completions,
+        };
+        arrangements.push_successors(0, 0, 0);
+        arrangements
+    }
+
+    // One arrangement picked uniformly at random: each choice is weighted by how many
+    // arrangements it leads to
+    fn sample(&self, rng: &mut Rng) -> Option<String> {
+        let completions = self.completions();
+        let total = completions[0].get(0, 0);
+        if total == 0 {
+            return None;
+        }
+
+        // The index of the chosen arrangement among those still reachable
+        let mut remaining = rng.below_u128(total);
+        let (mut group, mut run) = (0, 0);
+        let mut filled = Vec::with_capacity(self.conditions.len());
+        for (i, condition) in self.conditions.iter().enumerate() {
+            for (choice, next_group, next_run) in
+                transitions(&self.condition_counts, group, run, *condition)
             {
-                match condition {
-                    SpringCondition::Working => {
-                        // Not a match
-                        return 0;
-                    }
-                    SpringCondition::Damaged => {
-                        // Damaged spring, consume (by doing nothing)
-                    }
-                    SpringCondition::Unknown => {
-                        // An unknown position, assume that it's damaged
-                        consumed_unknown_damaged += 1;
-                    }
+                let ways = completions[i + 1].get(next_group, next_run);
+                if remaining < ways {
+                    filled.push(choice);
+                    (group, run) = (next_group, next_run);
+                    break;
                 }
+                remaining -= ways;
             }
+        }
+        Some(conditions_to_str(&filled))
+    }
 
-            // Check that we haven't consumed more unknown damaged than are available
-            if consumed_unknown_damaged > damaged_count {
-                return 0;
+    // Fills in every unknown spring that has the same condition in all arrangements, or returns
+    // None when there are no arrangements at all
+    fn settle(&self) -> Option<Vec<SpringCondition>> {
+        let prefixes = self.prefixes();
+        let completions = self.completions();
+        if completions[0].get(0, 0) == 0 {
+            return None;
+        }
+
+        let mut settled = self.conditions.clone();
+        for (i, condition) in self.conditions.iter().enumerate() {
+            if *condition != SpringCondition::Unknown {
+                continue;
             }
 
-            // If this isn't the end of the conditions vector, we have to check if the next value is a damaged spring or an unknown.
-            // If it's a damaged spring, we can early return.
-            // If it's an unknown, we consume it and remove it from the available working.
-            if conditions.len() > expected_contiguous_damaged_springs {
-                match conditions[expected_contiguous_damaged_springs] {
-                    SpringCondition::Working => {
-                        // All good, no need to do anything, we can consume as expected, including the working spring
-                    }
-                    SpringCondition::Damaged => {
-                        // Can't have more contiguous springs, not a valid variant
-                        return 0;
+            let (mut working, mut damaged) = (false, false);
+            for (group, runs) in prefixes[i].ways.iter().enumerate() {
+                for (run, &ways) in runs.iter().enumerate() {
+                    if ways == 0 {
+                        continue;
                     }
-                    SpringCondition::Unknown => {
-                        // An unknown spring, check if we have available working springs
-                        if working_count > 0 {
-                            // We do, consume it
-                            return check(
-                                conditions[expected_contiguous_damaged_springs + 1..].to_vec(),
-                                condition_counts[1..].to_vec(),
-                                working_count - 1,
-                                damaged_count - consumed_unknown_damaged,
-                            );
+                    for (choice, next_group, next_run) in
+                        transitions(&self.condition_counts, group, run, *condition)
+                    {
+                        if completions[i + 1].get(next_group, next_run) > 0 {
+                            working |= choice == SpringCondition::Working;
+                            damaged |= choice == SpringCondition::Damaged;
                         }
-
-                        // We don't have enough working springs, not a valid variant
-                        return 0;
                     }
                 }
             }
 
-            // At this point, we have either returned because there haven't been enough contiguous
-            // broken springs, or we have consumed known and/or unknown damaged springs.
+            settled[i] = match (working, damaged) {
+                (true, false) => SpringCondition::Working,
+                (false, true) => SpringCondition::Damaged,
+                _ => SpringCondition::Unknown,
+            };
+        }
+        Some(settled)
+    }
+}
 
-            // We can run the check function again without the consumed damaged springs with all values adjusted accordingly.
-            return check(
-                conditions[expected_contiguous_damaged_springs..].to_vec(),
-                condition_counts[1..].to_vec(),
-                working_count,
-                damaged_count - consumed_unknown_damaged,
-            );
+impl fmt::Display for Row {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let counts: Vec<String> = self
+            .condition_counts
+            .iter()
+            .map(|count| count.to_string())
+            .collect();
+        write!(
+            f,
+            "{} {}",
+            conditions_to_str(&self.conditions),
+            counts.join(",")
+        )
+    }
+}
+
+struct Arrangements<'a> {
+    row: &'a Row,
+    completions: Vec<States<'a>>,
+    filled: Vec<SpringCondition>,
+    // Choices still to explore: the position, what to fill it with and the state that leads to
+    stack: Vec<(usize, SpringCondition, usize, usize)>,
+    // An empty row has a single, empty arrangement when it has no groups
+    empty: bool,
+}
+
+impl Arrangements<'_> {
+    fn push_successors(&mut self, position: usize, group: usize, run: usize) {
+        let Some(condition) = self.row.conditions.get(position) else {
+            return;
+        };
+        let counts = &self.row.condition_counts;
+        for (choice, next_group, next_run) in transitions(counts, group, run, *condition).rev() {
+            if self.completions[position + 1].get(next_group, next_run) > 0 {
+                self.stack.push((position, choice, next_group, next_run));
+            }
+        }
+    }
+}
+
+impl Iterator for Arrangements<'_> {
+    type Item = String;
+
+    fn next(&mut self) -> Option<String> {
+        if self.empty {
+            self.empty = false;
+            return Some(String::new());
+        }
+
+        while let Some((position, choice, group, run)) = self.stack.pop() {
+            self.filled.truncate(position);
+            self.filled.push(choice);
+            if position + 1 == self.row.conditions.len() {
+                return Some(conditions_to_str(&self.filled));
+            }
+            self.push_successors(position + 1, group, run);
+        }
+        None
+    }
+}
+
+// Where a state goes on the next spring: a working spring only ends a run that matches its group,
+// and a damaged spring only extends a run that is still short of it. Unknown springs try both,
+// working first. There are at most two, so they come back without allocating.
+fn transitions(
+    condition_counts: &[usize],
+    group: usize,
+    run: usize,
+    condition: SpringCondition,
+) -> impl DoubleEndedIterator<Item = (SpringCondition, usize, usize)> {
+    let working = if condition == SpringCondition::Damaged {
+        None
+    } else if run == 0 {
+        Some((SpringCondition::Working, group, 0))
+    } else if run == condition_counts[group] {
+        Some((SpringCondition::Working, group + 1, 0))
+    } else {
+        None
+    };
+    let damaged = (condition != SpringCondition::Working
+        && condition_counts
+            .get(group)
+            .is_some_and(|count| run < *count))
+    .then_some((SpringCondition::Damaged, group, run + 1));
+    [working, damaged].into_iter().flatten()
+}
+
+// The number of ways to fill in some of the conditions, for every (group index, run length) pair:
+// how many groups have been completed, and how many damaged springs the current run holds towards
+// the next one
+#[derive(Debug, Clone)]
+struct States<'a> {
+    condition_counts: &'a [usize],
+    ways: Vec<Vec<u128>>,
+}
+
+impl<'a> States<'a> {
+    fn new(condition_counts: &'a [usize]) -> Self {
+        let mut states = Self::empty(condition_counts);
+        states.ways[0][0] = 1;
+        states
+    }
+
+    fn empty(condition_counts: &'a [usize]) -> Self {
+        // Once every group is complete, only an empty run remains possible
+        let ways = (0..=condition_counts.len())
+            .map(|group| vec![0; condition_counts.get(group).map_or(1, |count| count + 1)])
+            .collect();
+        Self {
+            condition_counts,
+            ways,
         }
-        SpringCondition::Unknown => {
-            if working_count != 0 {
-                // Ignore the first spring as if it was a working spring, and move on
-                sum += check(
-                    conditions[1..].to_vec(),
-                    condition_counts.clone(),
-                    working_count - 1,
-                    damaged_count,
-                )
+    }
+
+    // The states a row may end in: every group finished, or the last run still open at the end
+    fn accepting(condition_counts: &'a [usize]) -> Self {
+        let mut states = Self::empty(condition_counts);
+        states.ways[condition_counts.len()][0] = 1;
+        if let Some(last) = condition_counts.last() {
+            states.ways[condition_counts.len() - 1][*last] = 1;
+        }
+        states
+    }
+
+    fn get(&self, group: usize, run: usize) -> u128 {
+        self.ways[group][run]
+    }
+
+    fn add(&mut self, group: usize, run: usize, ways: u128) {
+        self.ways[group][run] = checked::add(self.ways[group][run], ways);
+    }
+
+    // Ways after one more spring, counting forwards from the start of the row
+    fn step(&self, condition: SpringCondition) -> Self {
+        let mut next = Self::empty(self.condition_counts);
+        for (group, runs) in self.ways.iter().enumerate() {
+            for (run, &ways) in runs.iter().enumerate() {
+                if ways == 0 {
+                    continue;
+                }
+                for (_, next_group, next_run) in
+                    transitions(self.condition_counts, group, run, condition)
+                {
+                    next.add(next_group, next_run, ways);
+                }
             }
+        }
+        next
+    }
 
-            if damaged_count != 0 {
-                // Replace the first spring as if it was a damaged spring
-                let mut conditions = conditions.clone();
-                conditions[0] = SpringCondition::Damaged;
-                sum += check(
-                    conditions,
-                    condition_counts.clone(),
-                    working_count,
-                    damaged_count - 1,
-                );
+    // Ways with one more spring in front, counting backwards from the end of the row
+    fn step_back(&self, condition: SpringCondition) -> Self {
+        let mut previous = Self::empty(self.condition_counts);
+        for group in 0..previous.ways.len() {
+            for run in 0..previous.ways[group].len() {
+                for (_, next_group, next_run) in
+                    transitions(self.condition_counts, group, run, condition)
+                {
+                    previous.add(group, run, self.get(next_group, next_run));
+                }
             }
         }
+        previous
     }
 
-    sum
+    // Ways that match every group, either finished or with the last run still open at the end
+    fn accepted(&self) -> u128 {
+        let groups = self.condition_counts.len();
+        let mut accepted = self.ways[groups][0];
+        if let Some(last) = self.condition_counts.last() {
+            accepted = checked::add(accepted, self.ways[groups - 1][*last]);
+        }
+        accepted
+    }
+
+    // Ways that can still be completed, given the ways to complete each state
+    fn live(&self, completions: &States) -> u128 {
+        let mut live: u128 = 0;
+        for (group, runs) in self.ways.iter().enumerate() {
+            for (run, &ways) in runs.iter().enumerate() {
+                if completions.get(group, run) > 0 {
+                    live = checked::add(live, ways);
+                }
+            }
+        }
+        live
+    }
 }
 
-fn get_variations(input: String) -> usize {
-    let rows: Vec<Row> = input
-        .split('\n')
-        .map(|arrangement| {
-            let (conditions, condition_counts) = arrangement.split_once(' ').unwrap();
-            let conditions = conditions.trim_matches('.');
-            let conditions: Vec<SpringCondition> = conditions
-                .chars()
-                .map(|char| match char {
-                    '.' => SpringCondition::Working,
-                    '#' => SpringCondition::Damaged,
-                    '?' => SpringCondition::Unknown,
-                    _ => panic!("Invalid spring condition"),
+type Grid = Vec<Vec<SpringCondition>>;
+
+// A nonogram is a day 12 row for every row and every column of a grid, all of which have to hold
+// at once. Lines are numbered rows first, then columns.
+#[derive(Debug, Clone)]
+struct Nonogram {
+    rows: Vec<Vec<usize>>,
+    columns: Vec<Vec<usize>>,
+    grid: Grid,
+}
+
+impl Nonogram {
+    // The row clues, a blank line, the column clues and optionally a blank line and a partially
+    // filled grid. Clues are written like day 12 group sizes, with 0 for a line with no groups.
+    fn parse(text: &str) -> Self {
+        let mut blocks = text.trim_end().split("\n\n");
+        let mut clues = || -> Vec<Vec<usize>> {
+            blocks
+                .next()
+                .expect("Missing nonogram clues")
+                .split('\n')
+                .map(|clue| match clue.trim() {
+                    "0" => Vec::new(),
+                    clue => parse_condition_counts(clue),
                 })
-                .collect();
-            let condition_counts: Vec<usize> = condition_counts
-                .split(',')
-                .map(|count| count.parse().unwrap())
-                .collect();
+                .collect()
+        };
+        let rows = clues();
+        let columns = clues();
+
+        let grid: Grid = match blocks.next() {
+            Some(grid) => grid.split('\n').map(parse_conditions).collect(),
+            None => vec![vec![SpringCondition::Unknown; columns.len()]; rows.len()],
+        };
+        assert_eq!(
+            grid.len(),
+            rows.len(),
+            "The grid doesn't have a row per clue"
+        );
+        for (y, row) in grid.iter().enumerate() {
+            assert_eq!(
+                row.len(),
+                columns.len(),
+                "Grid row {} doesn't have a cell per column clue",
+                y + 1
+            );
+        }
 
+        Self {
+            rows,
+            columns,
+            grid,
+        }
+    }
+
+    fn line(&self, grid: &Grid, line: usize) -> Row {
+        if line < self.rows.len() {
             Row {
-                conditions,
-                condition_counts,
+                conditions: grid[line].clone(),
+                condition_counts: self.rows[line].clone(),
             }
-        })
-        .collect();
+        } else {
+            let x = line - self.rows.len();
+            Row {
+                conditions: grid.iter().map(|row| row[x]).collect(),
+                condition_counts: self.columns[x].clone(),
+            }
+        }
+    }
 
-    let mut sum: usize = 0;
-    for row in rows {
-        let damaged_count = row
-            .conditions
-            .iter()
-            .filter(|condition| condition == &&SpringCondition::Damaged)
-            .count();
-        let unknown_count = row
-            .conditions
-            .iter()
-            .filter(|condition| condition == &&SpringCondition::Unknown)
-            .count();
+    // Settles lines until none of them changes, revisiting the lines crossing each newly filled
+    // cell. Returns false if some line can't be arranged.
+    fn propagate(&self, grid: &mut Grid) -> bool {
+        let height = self.rows.len();
+        let mut pending: Vec<usize> = (0..height + self.columns.len()).collect();
+        let mut queued = vec![true; pending.len()];
+
+        while let Some(line) = pending.pop() {
+            queued[line] = false;
+            let Some(settled) = self.line(grid, line).settle() else {
+                return false;
+            };
 
-        let actual_damaged_count: usize = row.condition_counts.iter().sum();
+            for (i, condition) in settled.into_iter().enumerate() {
+                let (x, y, crossing) = if line < height {
+                    (i, line, height + i)
+                } else {
+                    (line - height, i, i)
+                };
+                if grid[y][x] != condition {
+                    grid[y][x] = condition;
+                    if !queued[crossing] {
+                        queued[crossing] = true;
+                        pending.push(crossing);
+                    }
+                }
+            }
+        }
+        true
+    }
 
-        let missing_damaged_count = actual_damaged_count - damaged_count;
-        let missing_working_count = unknown_count - missing_damaged_count;
+    // Propagates, then guesses the first unknown cell both ways once that stalls. Stops after
+    // finding `limit` solutions.
+    fn solve(&self, mut grid: Grid, limit: usize, solutions: &mut Vec<Grid>) {
+        if solutions.len() >= limit || !self.propagate(&mut grid) {
+            return;
+        }
 
-        let result = check(
-            row.conditions,
-            row.condition_counts,
-            missing_working_count,
-            missing_damaged_count,
-        );
+        let unknown = grid.iter().enumerate().find_map(|(y, row)| {
+            row.iter()
+                .position(|condition| *condition == SpringCondition::Unknown)
+                .map(|x| (x, y))
+        });
+        let Some((x, y)) = unknown else {
+            solutions.push(grid);
+            return;
+        };
 
-        sum += result;
+        for guess in [SpringCondition::Damaged, SpringCondition::Working] {
+            let mut guessed = grid.clone();
+            guessed[y][x] = guess;
+            self.solve(guessed, limit, solutions);
+        }
     }
+}
 
-    sum
+fn print_grid(grid: &Grid) {
+    for row in grid {
+        println!("{}", conditions_to_str(row));
+    }
 }
 
-fn part_1() {
-    let data = fs::read_to_string("res/day_12.txt").unwrap();
-    let sum = get_variations(data);
+// Solves the nonogram in `path`, showing the cells line solving alone settles when it isn't
+// enough to tell a unique solution
+fn nonogram(path: &str) {
+    let text =
+        fs::read_to_string(path).unwrap_or_else(|error| panic!("Failed to read {path}: {error}"));
+    let nonogram = Nonogram::parse(&text);
 
-    println!("Part 1: {sum}");
+    let mut solutions = Vec::new();
+    nonogram.solve(nonogram.grid.clone(), 2, &mut solutions);
+
+    match solutions.len() {
+        0 => println!("No solution"),
+        1 => {
+            println!("Unique solution:");
+            print_grid(&solutions[0]);
+        }
+        _ => {
+            let mut settled = nonogram.grid.clone();
+            nonogram.propagate(&mut settled);
+            println!("Multiple solutions, agreeing on:");
+            print_grid(&settled);
+            for solution in &solutions {
+                println!();
+                println!("For example:");
+                print_grid(solution);
+            }
+        }
+    }
 }
 
-fn part_2() {
-    let data = fs::read_to_string("res/day_12.txt").unwrap();
-    let data = data
+// Trailing blank lines aren't rows, and a blank line anywhere else is a mistake rather than a row
+// with a single, empty arrangement
+fn parse_rows(data: &str) -> Vec<Row> {
+    data.trim_end_matches('\n')
         .split('\n')
-        .map(|line| {
-            let (conditions, condition_counts) = line.split_once(' ').unwrap();
-            let conditions = format!(
-                "{}?{}?{}?{}?{}",
-                conditions, conditions, conditions, conditions, conditions
-            );
-            let condition_counts = format!(
-                "{},{},{},{},{}",
-                condition_counts,
-                condition_counts,
-                condition_counts,
-                condition_counts,
-                condition_counts
-            );
-            format!("{} {}", conditions, condition_counts)
+        .enumerate()
+        .map(|(i, line)| {
+            assert!(!line.trim().is_empty(), "Line {} is blank", i + 1);
+            Row::parse(line)
         })
-        .collect::<Vec<String>>()
-        .join("\n");
+        .collect()
+}
+
+fn get_variations(rows: &[Row], factor: usize) -> u128 {
+    let mut sum: u128 = 0;
+    for row in rows {
+        sum = checked::add(sum, row.unfold(factor).arrangements());
+    }
+    sum
+}
 
-    let sum = get_variations(data);
+// Prints each arrangement of the row, stopping after `limit` of them. The rest are counted, not
+// enumerated, since unfolded rows can have far too many to walk through.
+fn list(row: &Row, limit: Option<usize>) {
+    let mut shown: u128 = 0;
+    for arrangement in row.iter().take(limit.unwrap_or(usize::MAX)) {
+        println!("  {arrangement}");
+        shown += 1;
+    }
+    let more = row.arrangements() - shown;
+    if more > 0 {
+        println!("  ... and {more} more");
+    }
+}
 
-    println!("Part 2: {sum}");
+// Prints how many ways each prefix of the row can be filled in without ruling out every
+// arrangement, which shows where the count grows and where it gets cut down
+fn explain(row: &Row) {
+    let prefixes = row.prefixes();
+    let completions = row.completions();
+    for i in 1..=row.conditions.len() {
+        println!(
+            "  {:<width$}  {}",
+            conditions_to_str(&row.conditions[..i]),
+            prefixes[i].live(&completions[i]),
+            width = row.conditions.len()
+        );
+    }
 }
 
 fn main() {
-    part_1();
-    part_2();
+    if let Some(path) = args::value("nonogram") {
+        nonogram(&path);
+        return;
+    }
+
+    let data = input::read(12);
+    let rows = parse_rows(&data);
+
+    println!("Part 1: {}", get_variations(&rows, 1));
+    println!("Part 2: {}", get_variations(&rows, 5));
+
+    let factor = args::value("unfold").map(|factor| {
+        let factor: usize = factor.parse().expect("Invalid unfold factor");
+        println!("Unfolded {factor} times: {}", get_variations(&rows, factor));
+        factor
+    });
+
+    // `--list`, `--sample` and `--explain` look at every row, or only at `--row N`, unfolded as
+    // many times as `--unfold` says
+    let list_arrangements = args::flag("list");
+    let sample = args::flag("sample");
+    let explain_counts = args::flag("explain");
+    if !(list_arrangements || sample || explain_counts) {
+        return;
+    }
+
+    let selected = args::value("row").map(|row| row.parse::<usize>().expect("Invalid row"));
+    let limit = args::value("limit").map(|limit| limit.parse().expect("Invalid limit"));
+    let seed = args::value("seed").map_or(0, |seed| seed.parse().expect("Invalid seed"));
+    let mut rng = Rng::new(seed);
+
+    for (i, row) in rows.iter().enumerate() {
+        if selected.is_some_and(|selected| selected != i + 1) {
+            continue;
+        }
+        let row = row.unfold(factor.unwrap_or(1));
+
+        println!();
+        println!("Row {}: {row} ({} arrangements)", i + 1, row.arrangements());
+        if explain_counts {
+            explain(&row);
+        }
+        if sample {
+            match row.sample(&mut rng) {
+                Some(arrangement) => println!("  Sample: {arrangement}"),
+                None => println!("  Sample: none"),
+            }
+        }
+        if list_arrangements {
+            list(&row, limit);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = include_str!("../../res/examples/day_12.txt");
+
+    #[test]
+    fn example() {
+        let rows = parse_rows(EXAMPLE);
+        assert_eq!(get_variations(&rows, 1), 21);
+        assert_eq!(get_variations(&rows, 5), 525152);
+    }
+
+    #[test]
+    fn trailing_blank_lines_are_not_rows() {
+        let rows = parse_rows(&format!("{EXAMPLE}\n\n"));
+        assert_eq!(rows.len(), 6);
+        assert_eq!(get_variations(&rows, 1), 21);
+        assert_eq!(get_variations(&rows, 5), 525152);
+    }
+
+    #[test]
+    #[should_panic(expected = "Line 2 is blank")]
+    fn blank_lines_between_rows_are_rejected() {
+        parse_rows("???.### 1,1,3\n\n.??..??...?##. 1,1,3");
+    }
 }
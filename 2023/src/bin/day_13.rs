@@ -1,4 +1,4 @@
-use std::fs;
+use advent_of_code_2023::{checked, input};
 
 type Pattern = Vec<Vec<bool>>;
 
@@ -34,7 +34,7 @@ fn check_symmetry(pattern: &Pattern, disallowed_result: usize) -> usize {
 }
 
 fn main() {
-    let data = fs::read_to_string("res/day_13.txt").unwrap();
+    let data = input::read(13);
 
     let patterns = data.split("\n\n");
     let patterns: Vec<Pattern> = patterns
@@ -54,7 +54,7 @@ fn main() {
         let mut result_1 = check_symmetry(&pattern, 0) * 100;
 
         if result_1 != 0 {
-            sum_1 += result_1;
+            sum_1 = checked::add(sum_1, result_1);
             // We found vertical symmetry, move on to the next pattern
         } else {
             // Check for horizontal symmetry
@@ -64,7 +64,7 @@ fn main() {
                 .collect();
 
             result_1 = check_symmetry(&rotated_pattern, 0);
-            sum_1 += result_1;
+            sum_1 = checked::add(sum_1, result_1);
         }
         let result_1 = result_1;
 
@@ -84,7 +84,7 @@ fn main() {
                 }
 
                 if result_2 != 0 {
-                    sum_2 += result_2;
+                    sum_2 = checked::add(sum_2, result_2);
                     // We found vertical symmetry, move on to the next pattern
                     continue 'outer;
                 }
@@ -107,7 +107,7 @@ fn main() {
                     continue;
                 }
 
-                sum_2 += result_2;
+                sum_2 = checked::add(sum_2, result_2);
                 continue 'outer;
             }
         }
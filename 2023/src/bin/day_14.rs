@@ -1,4 +1,11 @@
-use std::{collections::HashMap, fs};
+use std::collections::HashMap;
+
+use advent_of_code_2023::{
+    args, checked, image, input,
+    render::{self, Animation, Colour, Renderer, Style},
+};
+
+const CYCLES: usize = 1_000_000_000;
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
 enum Rock {
@@ -9,22 +16,17 @@ enum Rock {
 
 type Grid = Vec<Vec<Rock>>;
 
-#[allow(dead_code)]
-fn draw_grid(grid: &Grid) {
-    for row in grid.iter() {
-        for col in row.iter() {
-            match col {
-                Rock::Round => print!("O"),
-                Rock::Cube => print!("#"),
-                Rock::None => print!("."),
-            }
-        }
-        println!()
-    }
+fn draw_grid(grid: &Grid) -> String {
+    Renderer::styled(|rock: &Rock| match rock {
+        Rock::Round => ('O', Style::foreground(Colour::Yellow)),
+        Rock::Cube => ('#', Style::PLAIN),
+        Rock::None => ('.', Style::PLAIN),
+    })
+    .render(grid)
 }
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_14.txt").unwrap();
+    let data = input::read(14);
 
     let grid: Grid = data
         .split('\n')
@@ -65,7 +67,8 @@ fn part_1() {
 
     let mut sum: usize = 0;
     for (i, row) in ordered_grid.iter().rev().enumerate() {
-        sum += row.iter().filter(|rock| rock == &&Rock::Round).count() * (i + 1);
+        let round_rocks = row.iter().filter(|rock| rock == &&Rock::Round).count();
+        sum = checked::add(sum, checked::mul(round_rocks, i + 1));
     }
 
     println!("Part 1: {sum}");
@@ -115,28 +118,13 @@ fn reverse_grid(grid: Grid) -> Grid {
     ordered_grid
 }
 
-fn part_2() {
-    let data = fs::read_to_string("res/day_14.txt").unwrap();
-
-    let mut grid: Grid = data
-        .split('\n')
-        .map(|line| {
-            line.chars()
-                .map(|char| match char {
-                    'O' => Rock::Round,
-                    '#' => Rock::Cube,
-                    '.' => Rock::None,
-                    _ => panic!("Invalid rock type"),
-                })
-                .collect()
-        })
-        .collect();
-
+// Runs `cycles` spin cycles, skipping ahead once the platform starts repeating itself
+fn spin(mut grid: Grid, cycles: usize, mut animation: Option<&mut Animation>) -> Grid {
     let mut cache = HashMap::new();
     let mut cycle_detected = false;
     let mut i: usize = 0;
 
-    while i < 1_000_000_000 {
+    while i < cycles {
         grid = flip_grid(grid);
         grid = order_grid(grid);
         grid = flip_grid(grid);
@@ -146,12 +134,16 @@ fn part_2() {
         grid = flip_grid(grid);
         grid = reverse_grid(grid);
 
+        if let Some(animation) = animation.as_mut() {
+            animation.frame(&draw_grid(&grid));
+        }
+
         i += 1;
         if !cycle_detected {
             if let Some(cycle) = cache.get(&grid) {
                 cycle_detected = true;
                 let cycle_size = i - cycle;
-                let remaining_steps = 1_000_000_000 - i;
+                let remaining_steps = cycles - i;
                 let remaining_full_steps = remaining_steps / cycle_size;
 
                 i += remaining_full_steps * cycle_size;
@@ -162,9 +154,47 @@ fn part_2() {
         }
     }
 
+    grid
+}
+
+fn part_2() {
+    let data = input::read(14);
+
+    let grid: Grid = data
+        .split('\n')
+        .map(|line| {
+            line.chars()
+                .map(|char| match char {
+                    'O' => Rock::Round,
+                    '#' => Rock::Cube,
+                    '.' => Rock::None,
+                    _ => panic!("Invalid rock type"),
+                })
+                .collect()
+        })
+        .collect();
+
+    let mut animation = render::enabled().then(Animation::new);
+    let final_grid = spin(grid.clone(), CYCLES, animation.as_mut());
+
+    if let Some(path) = image::render_path() {
+        // `--cycles` picks which platform state to render, defaulting to the final one
+        let grid = match args::value("cycles") {
+            Some(cycles) => spin(grid, cycles.parse().expect("Invalid cycle count"), None),
+            None => final_grid.clone(),
+        };
+        image::write_grid(&path, &grid, |_, _, rock| match rock {
+            Rock::Round => image::YELLOW,
+            Rock::Cube => image::GREY,
+            Rock::None => image::BLACK,
+        })
+        .unwrap_or_else(|error| image::fail(&path, error));
+    }
+
     let mut sum: usize = 0;
-    for (i, row) in grid.iter().rev().enumerate() {
-        sum += row.iter().filter(|rock| rock == &&Rock::Round).count() * (i + 1);
+    for (i, row) in final_grid.iter().rev().enumerate() {
+        let round_rocks = row.iter().filter(|rock| rock == &&Rock::Round).count();
+        sum = checked::add(sum, checked::mul(round_rocks, i + 1));
     }
 
     println!("Part 2: {sum}");
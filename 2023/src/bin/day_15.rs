@@ -1,4 +1,7 @@
-use std::fs;
+use advent_of_code_2023::{
+    checked::{self, CheckedIterator},
+    input,
+};
 
 fn get_hash(label: &str) -> u8 {
     let mut hash = 0;
@@ -11,10 +14,10 @@ fn get_hash(label: &str) -> u8 {
 }
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_15.txt").unwrap();
+    let data = input::read(15);
     let parts = data.split(',');
 
-    let sum: usize = parts.map(|part| get_hash(part) as usize).sum();
+    let sum: usize = parts.map(|part| get_hash(part) as usize).checked_sum();
 
     println!("Part 1: {sum}");
 }
@@ -26,7 +29,7 @@ struct Lens<'a> {
 }
 
 fn part_2() {
-    let data = fs::read_to_string("res/day_15.txt").unwrap();
+    let data = input::read(15);
     let steps = data.split(',');
 
     let mut boxes = vec![Vec::new(); 256];
@@ -60,7 +63,10 @@ fn part_2() {
     let mut sum: usize = 0;
     for (i, box_) in boxes.iter().enumerate() {
         for (j, lens) in box_.iter().enumerate() {
-            sum += (i + 1) * (j + 1) * lens.focal_length as usize;
+            sum = checked::add(
+                sum,
+                checked::product([i + 1, j + 1, lens.focal_length as usize]),
+            );
         }
     }
 
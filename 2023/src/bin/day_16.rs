@@ -1,4 +1,9 @@
-use std::{collections::HashSet, fs, thread};
+use std::collections::HashSet;
+
+use advent_of_code_2023::{
+    image, input, parallel,
+    render::{self, Animation, Colour, Renderer, Style},
+};
 
 #[derive(Debug, Clone, Copy)]
 enum CellType {
@@ -9,12 +14,6 @@ enum CellType {
     None,
 }
 
-#[derive(Debug, Clone, Copy)]
-struct Cell {
-    cell_type: CellType,
-    energized: bool,
-}
-
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 enum Direction {
     North,
@@ -29,29 +28,18 @@ struct Vector {
     direction: Direction,
 }
 
-type Grid = Vec<Vec<Cell>>;
-
-#[allow(dead_code)]
-fn draw_grid(grid: &Grid) {
-    for row in grid {
-        for cell in row {
-            let char = match cell.cell_type {
-                CellType::RightMirror => '/',
-                CellType::LeftMirror => '\\',
-                CellType::VerticalSplitter => '|',
-                CellType::HorizontalSplitter => '-',
-                CellType::None => '·',
-            };
-
-            if cell.energized {
-                print!("\x1b[0;41m{char}\x1b[0m");
-            } else {
-                print!("{char}");
-            }
-        }
-        println!();
-    }
-    println!();
+type Grid = Vec<Vec<CellType>>;
+
+fn draw_grid(grid: &Grid, energized: &[Vec<bool>]) -> String {
+    Renderer::new(|cell: &CellType| match cell {
+        CellType::RightMirror => '/',
+        CellType::LeftMirror => '\\',
+        CellType::VerticalSplitter => '|',
+        CellType::HorizontalSplitter => '-',
+        CellType::None => '·',
+    })
+    .highlight(Style::background(Colour::Red), |x, y| energized[y][x])
+    .render(grid)
 }
 
 fn go_north(queue: &mut Vec<Vector>, vector: &Vector) {
@@ -95,26 +83,25 @@ fn parse_grid(input: &str) -> Grid {
         .split('\n')
         .map(|line| {
             line.chars()
-                .map(|char| {
-                    let cell_type = match char {
-                        '/' => CellType::RightMirror,
-                        '\\' => CellType::LeftMirror,
-                        '|' => CellType::VerticalSplitter,
-                        '-' => CellType::HorizontalSplitter,
-                        '.' => CellType::None,
-                        _ => panic!("Invalid cell type"),
-                    };
-                    Cell {
-                        cell_type,
-                        energized: false,
-                    }
+                .map(|char| match char {
+                    '/' => CellType::RightMirror,
+                    '\\' => CellType::LeftMirror,
+                    '|' => CellType::VerticalSplitter,
+                    '-' => CellType::HorizontalSplitter,
+                    '.' => CellType::None,
+                    _ => panic!("Invalid cell type"),
                 })
                 .collect()
         })
         .collect()
 }
 
-fn run(mut grid: Grid, starting_vector: Vector) -> usize {
+fn energize(
+    grid: &Grid,
+    starting_vector: Vector,
+    mut animation: Option<&mut Animation>,
+) -> Vec<Vec<bool>> {
+    let mut energized = vec![vec![false; grid[0].len()]; grid.len()];
     let mut queue = vec![starting_vector];
     let mut visited = HashSet::new();
 
@@ -125,12 +112,19 @@ fn run(mut grid: Grid, starting_vector: Vector) -> usize {
             visited.insert(vector);
         }
 
-        let cell = grid[vector.position.1][vector.position.0];
-        grid[vector.position.1][vector.position.0].energized = true;
-        match cell.cell_type {
+        energized[vector.position.1][vector.position.0] = true;
+
+        // One frame per row's worth of beam steps keeps the animation a sensible length
+        if let Some(animation) = animation.as_mut() {
+            if visited.len() % grid[0].len() == 0 {
+                animation.frame(&draw_grid(grid, &energized));
+            }
+        }
+
+        match grid[vector.position.1][vector.position.0] {
             CellType::RightMirror => match vector.direction {
                 Direction::North => {
-                    go_east(&grid, &mut queue, &vector);
+                    go_east(grid, &mut queue, &vector);
                 }
                 Direction::South => {
                     go_west(&mut queue, &vector);
@@ -139,7 +133,7 @@ fn run(mut grid: Grid, starting_vector: Vector) -> usize {
                     go_north(&mut queue, &vector);
                 }
                 Direction::West => {
-                    go_south(&grid, &mut queue, &vector);
+                    go_south(grid, &mut queue, &vector);
                 }
             },
             CellType::LeftMirror => match vector.direction {
@@ -147,10 +141,10 @@ fn run(mut grid: Grid, starting_vector: Vector) -> usize {
                     go_west(&mut queue, &vector);
                 }
                 Direction::South => {
-                    go_east(&grid, &mut queue, &vector);
+                    go_east(grid, &mut queue, &vector);
                 }
                 Direction::East => {
-                    go_south(&grid, &mut queue, &vector);
+                    go_south(grid, &mut queue, &vector);
                 }
                 Direction::West => {
                     go_north(&mut queue, &vector);
@@ -158,113 +152,113 @@ fn run(mut grid: Grid, starting_vector: Vector) -> usize {
             },
             CellType::VerticalSplitter => match vector.direction {
                 Direction::North => go_north(&mut queue, &vector),
-                Direction::South => go_south(&grid, &mut queue, &vector),
+                Direction::South => go_south(grid, &mut queue, &vector),
                 Direction::East | Direction::West => {
                     go_north(&mut queue, &vector);
-                    go_south(&grid, &mut queue, &vector);
+                    go_south(grid, &mut queue, &vector);
                 }
             },
             CellType::HorizontalSplitter => match vector.direction {
-                Direction::East => go_east(&grid, &mut queue, &vector),
+                Direction::East => go_east(grid, &mut queue, &vector),
                 Direction::West => go_west(&mut queue, &vector),
                 Direction::North | Direction::South => {
-                    go_east(&grid, &mut queue, &vector);
+                    go_east(grid, &mut queue, &vector);
                     go_west(&mut queue, &vector);
                 }
             },
             CellType::None => match vector.direction {
                 Direction::North => go_north(&mut queue, &vector),
-                Direction::South => go_south(&grid, &mut queue, &vector),
-                Direction::East => go_east(&grid, &mut queue, &vector),
+                Direction::South => go_south(grid, &mut queue, &vector),
+                Direction::East => go_east(grid, &mut queue, &vector),
                 Direction::West => go_west(&mut queue, &vector),
             },
         }
     }
 
-    grid.iter().flatten().filter(|cell| cell.energized).count()
+    if let Some(animation) = animation {
+        animation.frame(&draw_grid(grid, &energized));
+    }
+
+    energized
+}
+
+fn run(grid: &Grid, starting_vector: Vector) -> usize {
+    energize(grid, starting_vector, None)
+        .iter()
+        .flatten()
+        .filter(|energized| **energized)
+        .count()
 }
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_16.txt").unwrap();
+    let data = input::read(16);
 
     let grid = parse_grid(&data);
     let starting_vector = Vector {
         position: (0, 0),
         direction: Direction::East,
     };
-    let energized_cells = run(grid, starting_vector);
+    let mut animation = render::enabled().then(Animation::new);
+    let energized = energize(&grid, starting_vector, animation.as_mut());
+
+    if let Some(path) = image::render_path() {
+        image::write_grid(&path, &grid, |x, y, cell| {
+            if energized[y][x] {
+                image::YELLOW
+            } else if let CellType::None = cell {
+                image::BLACK
+            } else {
+                image::GREY
+            }
+        })
+        .unwrap_or_else(|error| image::fail(&path, error));
+    }
+
+    let energized_cells = energized
+        .iter()
+        .flatten()
+        .filter(|energized| **energized)
+        .count();
 
     println!("Part 1: {energized_cells}");
 }
 
 fn part_2() {
-    let data = fs::read_to_string("res/day_16.txt").unwrap();
+    let data = input::read(16);
 
     let grid = parse_grid(&data);
 
-    let mut threads = Vec::with_capacity(grid.len() + grid[0].len());
-
-    for (x, _) in grid[0].iter().enumerate() {
-        let grid = grid.clone();
-        let thread = thread::spawn(move || {
-            let north_run = run(
-                grid.clone(),
-                Vector {
-                    position: (x, grid.len() - 1),
-                    direction: Direction::North,
-                },
-            );
+    let height = grid.len();
+    let width = grid[0].len();
 
-            let south_run = run(
-                grid.clone(),
-                Vector {
-                    position: (x, 0),
-                    direction: Direction::South,
-                },
-            );
-
-            if south_run > north_run {
-                return south_run;
-            }
-
-            north_run
+    let mut starting_vectors = Vec::with_capacity(2 * (width + height));
+    for x in 0..width {
+        starting_vectors.push(Vector {
+            position: (x, height - 1),
+            direction: Direction::North,
+        });
+        starting_vectors.push(Vector {
+            position: (x, 0),
+            direction: Direction::South,
         });
-        threads.push(thread);
     }
-
-    for (y, _) in grid.iter().enumerate() {
-        let grid = grid.clone();
-        let thread = thread::spawn(move || {
-            let east_run = run(
-                grid.clone(),
-                Vector {
-                    position: (0, y),
-                    direction: Direction::East,
-                },
-            );
-
-            let west_run = run(
-                grid.clone(),
-                Vector {
-                    position: (grid[0].len() - 1, y),
-                    direction: Direction::West,
-                },
-            );
-
-            if west_run > east_run {
-                return west_run;
-            }
-
-            east_run
+    for y in 0..height {
+        starting_vectors.push(Vector {
+            position: (0, y),
+            direction: Direction::East,
+        });
+        starting_vectors.push(Vector {
+            position: (width - 1, y),
+            direction: Direction::West,
         });
-        threads.push(thread);
     }
 
-    let max_energized = threads
-        .into_iter()
-        .map(|thread| thread.join().unwrap())
-        .max()
-        .unwrap();
+    let max_energized = parallel::map_reduce(
+        &starting_vectors,
+        |&starting_vector| run(&grid, starting_vector),
+        usize::max,
+    )
+    .unwrap();
 
     println!("Part 2: {max_energized}");
 }
@@ -1,4 +1,10 @@
-use std::fs;
+use std::path::PathBuf;
+
+use advent_of_code_2023::{
+    checked::{self, CheckedIterator},
+    image::{self, Svg},
+    input,
+};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum Direction {
@@ -26,11 +32,10 @@ struct Instruction {
     distance: usize,
 }
 
-fn get_area(instructions: &[Instruction]) -> usize {
+fn get_vertices(instructions: &[Instruction]) -> Vec<(isize, isize)> {
     let mut vertices = Vec::with_capacity(instructions.len());
     let mut current_x = 0;
     let mut current_y = 0;
-    let mut perimeter = 0;
     for instruction in instructions {
         vertices.push((current_x, current_y));
         match instruction.direction {
@@ -39,28 +44,36 @@ fn get_area(instructions: &[Instruction]) -> usize {
             Direction::Left => current_x -= instruction.distance as isize,
             Direction::Right => current_x += instruction.distance as isize,
         }
-        perimeter += instruction.distance;
     }
+    vertices
+}
+
+fn get_area(instructions: &[Instruction]) -> usize {
+    let vertices = get_vertices(instructions);
+    let perimeter: usize = instructions
+        .iter()
+        .map(|instruction| instruction.distance)
+        .checked_sum();
 
     let last_vertex = vertices.len() - 1;
     let mut sum_1 = 0;
     let mut sum_2 = 0;
 
     for i in 0..last_vertex {
-        sum_1 += vertices[i].0 * vertices[i + 1].1;
-        sum_2 += vertices[i].1 * vertices[i + 1].0;
+        sum_1 = checked::add(sum_1, checked::mul(vertices[i].0, vertices[i + 1].1));
+        sum_2 = checked::add(sum_2, checked::mul(vertices[i].1, vertices[i + 1].0));
     }
 
-    sum_1 += vertices[last_vertex].0 * vertices[0].1;
-    sum_2 += vertices[0].0 * vertices[last_vertex].1;
+    sum_1 = checked::add(sum_1, checked::mul(vertices[last_vertex].0, vertices[0].1));
+    sum_2 = checked::add(sum_2, checked::mul(vertices[0].0, vertices[last_vertex].1));
 
-    let area = (sum_1 - sum_2).unsigned_abs() / 2;
+    let area = checked::sub(sum_1, sum_2).unsigned_abs() / 2;
 
-    area + (perimeter / 2) + 1
+    checked::sum([area, perimeter / 2, 1])
 }
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_18.txt").unwrap();
+    let data = input::read(18);
     let instructions: Vec<Instruction> = data
         .lines()
         .map(|line| {
@@ -79,8 +92,8 @@ fn part_1() {
     println!("Part 1: {area}");
 }
 
-fn part_2() {
-    let data = fs::read_to_string("res/day_18.txt").unwrap();
+fn part_2(render_path: Option<PathBuf>) {
+    let data = input::read(18);
 
     let instructions: Vec<Instruction> = data
         .lines()
@@ -97,10 +110,24 @@ fn part_2() {
 
     let total_area = get_area(&instructions);
 
+    // Part 2's coordinates are far too large for a grid, so the lagoon is drawn as a polygon
+    if let Some(path) = render_path {
+        let vertices: Vec<(i64, i64)> = get_vertices(&instructions)
+            .into_iter()
+            .map(|(x, y)| (x as i64, y as i64))
+            .collect();
+        let mut svg = Svg::new();
+        svg.polygon(&vertices, image::BLUE, image::BLACK);
+        svg.write(&path)
+            .unwrap_or_else(|error| image::fail(&path, error));
+    }
+
     println!("Part 2: {total_area}");
 }
 
 fn main() {
+    // Only SVG can hold part 2's huge coordinates, so other formats are rejected before solving
+    let render_path = image::svg_render_path();
     part_1();
-    part_2();
+    part_2(render_path);
 }
@@ -1,13 +1,17 @@
-use std::fs;
+use std::{collections::HashMap, fs};
 
+use advent_of_code_2023::{
+    args,
+    checked::{self, CheckedIterator},
+    input,
+};
 use lazy_regex::regex;
 
-#[derive(Debug)]
-struct Pull {
-    red: u32,
-    green: u32,
-    blue: u32,
-}
+// How many cubes of each colour were shown in one pull, in the order they were listed
+type Pull = Vec<(String, u32)>;
+
+// How many cubes of each colour the bag holds. Colours missing from the bag have no cubes.
+type Bag = HashMap<String, u32>;
 
 #[derive(Debug)]
 struct Game {
@@ -15,31 +19,76 @@ struct Game {
     pulls: Vec<Pull>,
 }
 
-const MAX_RED: u32 = 12;
-const MAX_GREEN: u32 = 13;
-const MAX_BLUE: u32 = 14;
+impl Game {
+    // The fewest cubes of each colour that make every pull possible
+    fn minimal_bag(&self) -> Bag {
+        let mut bag = Bag::new();
+        for (colour, count) in self.pulls.iter().flatten() {
+            let max = bag.entry(colour.clone()).or_insert(0);
+            *max = (*max).max(*count);
+        }
+        bag
+    }
+
+    fn possible_with(&self, bag: &Bag) -> bool {
+        self.pulls
+            .iter()
+            .flatten()
+            .all(|(colour, count)| bag.get(colour).is_some_and(|available| count <= available))
+    }
+}
+
+const DEFAULT_BAG: &str = "red=12,green=13,blue=14";
+
+// Parses `colour=count` pairs separated by commas or newlines
+fn parse_bag(bag: &str) -> Bag {
+    bag.split([',', '\n'])
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+        .map(|entry| {
+            let (colour, count) = entry
+                .split_once('=')
+                .unwrap_or_else(|| panic!("Invalid bag entry: {entry}"));
+            let count = count
+                .trim()
+                .parse()
+                .unwrap_or_else(|_| panic!("Invalid cube count: {entry}"));
+            (colour.trim().to_string(), count)
+        })
+        .collect()
+}
+
+// The bag comes from `--bag red=12,...`, or a file of the same entries given with `--bag-file`
+fn read_bag() -> Bag {
+    if let Some(bag) = args::value("bag") {
+        return parse_bag(&bag);
+    }
+    if let Some(path) = args::value("bag-file") {
+        let bag = fs::read_to_string(&path)
+            .unwrap_or_else(|error| panic!("Failed to read {path}: {error}"));
+        return parse_bag(&bag);
+    }
+    parse_bag(DEFAULT_BAG)
+}
 
 fn parse_game(line: &str) -> Game {
     let game_number_re = regex!("Game (?<id>\\d+):(?<pulls>.*)");
-    let red_re = regex!("(?<red>\\d+) red");
-    let green_re = regex!("(?<green>\\d+) green");
-    let blue_re = regex!("(?<blue>\\d+) blue");
+    let cubes_re = regex!("(?<count>\\d+) (?<colour>\\w+)");
 
     let capture = game_number_re.captures(line).unwrap();
     let game_number = capture.name("id").unwrap().as_str();
     let pulls = capture.name("pulls").unwrap().as_str();
     let pulls = pulls.split(';').map(|pull| {
-        let red: u32 = red_re
-            .captures(pull)
-            .map_or(0, |r| r.name("red").unwrap().as_str().parse().unwrap());
-        let green: u32 = green_re
-            .captures(pull)
-            .map_or(0, |r| r.name("green").unwrap().as_str().parse().unwrap());
-        let blue: u32 = blue_re
-            .captures(pull)
-            .map_or(0, |r| r.name("blue").unwrap().as_str().parse().unwrap());
-
-        Pull { red, blue, green }
+        let mut cubes = Pull::new();
+        for capture in cubes_re.captures_iter(pull) {
+            let count: u32 = capture.name("count").unwrap().as_str().parse().unwrap();
+            let colour = capture.name("colour").unwrap().as_str();
+            match cubes.iter_mut().find(|(seen, _)| seen == colour) {
+                Some((_, total)) => *total += count,
+                None => cubes.push((colour.to_string(), count)),
+            }
+        }
+        cubes
     });
 
     Game {
@@ -49,50 +98,66 @@ fn parse_game(line: &str) -> Game {
 }
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_2.txt").unwrap();
+    let data = input::read(2);
     let lines = data.split('\n');
+    let bag = read_bag();
 
     let sum: u32 = lines
         .map(parse_game)
-        .filter(|game| {
-            !game
-                .pulls
-                .iter()
-                .any(|pull| pull.red > MAX_RED || pull.green > MAX_GREEN || pull.blue > MAX_BLUE)
-        })
+        .filter(|game| game.possible_with(&bag))
         .map(|game| game.id)
-        .sum();
+        .checked_sum();
 
     println!("Part 1: {sum}");
 }
 
 fn part_2() {
-    let data = fs::read_to_string("res/day_2.txt").unwrap();
-    let lines = data.split('\n');
+    let data = input::read(2);
+    let games: Vec<Game> = data.split('\n').map(parse_game).collect();
+
+    // Every colour seen in any game, in the order it first appears. A game that never shows one
+    // of them needs none of it, which makes its power zero.
+    let mut colours: Vec<&str> = Vec::new();
+    for (colour, _) in games.iter().flat_map(|game| game.pulls.iter().flatten()) {
+        if !colours.contains(&colour.as_str()) {
+            colours.push(colour);
+        }
+    }
 
-    let sum: u32 = lines
-        .map(parse_game)
+    // `--detailed` lists each game's minimal bag and power after the answer
+    let detailed = args::flag("detailed");
+    let mut breakdown = Vec::new();
+    let sum: u32 = games
+        .iter()
         .map(|game| {
-            let mut max_red = 0;
-            let mut max_green = 0;
-            let mut max_blue = 0;
-            game.pulls.iter().for_each(|pull| {
-                if pull.red > max_red {
-                    max_red = pull.red;
-                }
-                if pull.green > max_green {
-                    max_green = pull.green;
-                }
-                if pull.blue > max_blue {
-                    max_blue = pull.blue;
-                }
-            });
-
-            max_red * max_green * max_blue
+            let bag = game.minimal_bag();
+            let counts: Vec<u32> = colours
+                .iter()
+                .map(|colour| bag.get(*colour).copied().unwrap_or(0))
+                .collect();
+            let power = checked::product(counts.iter().copied());
+
+            if detailed {
+                let cubes: Vec<String> = colours
+                    .iter()
+                    .zip(&counts)
+                    .map(|(colour, count)| format!("{count} {colour}"))
+                    .collect();
+                breakdown.push(format!(
+                    "Game {}: {} (power {power})",
+                    game.id,
+                    cubes.join(", ")
+                ));
+            }
+
+            power
         })
-        .sum();
+        .checked_sum();
 
     println!("Part 2: {sum}");
+    for game in breakdown {
+        println!("  {game}");
+    }
 }
 
 fn main() {
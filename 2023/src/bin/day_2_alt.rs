@@ -1,5 +1,7 @@
-use std::fs;
-
+use advent_of_code_2023::{
+    checked::{self, CheckedIterator},
+    input,
+};
 use lazy_regex::regex;
 
 const MAX_RED: u32 = 12;
@@ -7,7 +9,7 @@ const MAX_GREEN: u32 = 13;
 const MAX_BLUE: u32 = 14;
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_2.txt").unwrap();
+    let data = input::read(2);
     let lines = data.split('\n');
 
     let game_number_re = regex!("Game (?<id>\\d+):.*");
@@ -69,13 +71,13 @@ fn part_1() {
                 .parse::<u32>()
                 .unwrap()
         })
-        .sum();
+        .checked_sum();
 
     println!("Part 1: {sum}");
 }
 
 fn part_2() {
-    let data = fs::read_to_string("res/day_2.txt").unwrap();
+    let data = input::read(2);
     let lines = data.split('\n');
 
     let red_re = regex!("(?<red>\\d+) red");
@@ -112,11 +114,11 @@ fn part_2() {
                 .max()
                 .unwrap();
 
-            red * green * blue
+            checked::product([red, green, blue])
         })
-        .sum();
+        .checked_sum();
 
-    println!("Part 1: {sum}");
+    println!("Part 2: {sum}");
 }
 
 fn main() {
@@ -1,178 +1,155 @@
-use std::{collections::BTreeMap, fs};
-
-#[derive(Debug, Eq, PartialEq)]
-enum Cell {
-    Digit(u8),
-    Symbol,
-    Gear(usize, usize),
-    Empty,
+use std::collections::{BTreeMap, HashMap};
+
+use advent_of_code_2023::{
+    args,
+    checked::{self, CheckedIterator},
+    input,
+};
+
+// A run of digits on one row, covering columns `start..end`
+#[derive(Debug)]
+struct Number {
+    value: u32,
+    row: usize,
+    start: usize,
+    end: usize,
 }
 
-#[inline]
-fn is_symbol_adjacent(grid: &Vec<Vec<Cell>>, row: usize, col: usize) -> bool {
-    if row > 0 && col > 0 && grid[row - 1][col - 1] == Cell::Symbol {
-        return true;
-    }
-    if row > 0 && grid[row - 1][col] == Cell::Symbol {
-        return true;
-    }
-    if row > 0 && col + 1 < grid[row].len() && grid[row - 1][col + 1] == Cell::Symbol {
-        return true;
-    }
-    if col > 0 && grid[row][col - 1] == Cell::Symbol {
-        return true;
-    }
-    if col + 1 < grid[row].len() && grid[row][col + 1] == Cell::Symbol {
-        return true;
-    }
-    if row + 1 < grid.len() && col > 0 && grid[row + 1][col - 1] == Cell::Symbol {
-        return true;
-    }
-    if row + 1 < grid.len() && grid[row + 1][col] == Cell::Symbol {
-        return true;
-    }
-    if row + 1 < grid.len() && col + 1 < grid[row].len() && grid[row + 1][col + 1] == Cell::Symbol {
-        return true;
-    }
+#[derive(Debug)]
+struct Symbol {
+    char: char,
+    row: usize,
+    col: usize,
+}
 
-    false
+#[derive(Debug)]
+struct Schematic {
+    numbers: Vec<Number>,
+    symbols: Vec<Symbol>,
+    // Indices of the numbers touching each symbol, and of the symbols touching each number
+    symbol_parts: Vec<Vec<usize>>,
+    number_symbols: Vec<Vec<usize>>,
 }
 
-#[inline]
-fn is_gear_adjacent(grid: &Vec<Vec<Cell>>, row: usize, col: usize) -> Option<(usize, usize)> {
-    if row > 0 {
-        if col > 0 {
-            if let Cell::Gear(x, y) = grid[row - 1][col - 1] {
-                return Some((x, y));
+impl Schematic {
+    fn parse(data: &str) -> Self {
+        let mut numbers = Vec::new();
+        let mut symbols = Vec::new();
+
+        for (row, line) in data.split('\n').enumerate() {
+            let mut number: Option<Number> = None;
+            for (col, char) in line.chars().enumerate() {
+                if let Some(digit) = char.to_digit(10) {
+                    let number = number.get_or_insert(Number {
+                        value: 0,
+                        row,
+                        start: col,
+                        end: col,
+                    });
+                    number.value = checked::add(checked::mul(number.value, 10), digit);
+                    number.end = col + 1;
+                    continue;
+                }
+
+                numbers.extend(number.take());
+                if char != '.' {
+                    symbols.push(Symbol { char, row, col });
+                }
             }
+            numbers.extend(number);
         }
-        if let Cell::Gear(x, y) = grid[row - 1][col] {
-            return Some((x, y));
-        }
-        if col + 1 < grid[row].len() {
-            if let Cell::Gear(x, y) = grid[row - 1][col + 1] {
-                return Some((x, y));
+
+        let positions: HashMap<(usize, usize), usize> = symbols
+            .iter()
+            .enumerate()
+            .map(|(i, symbol)| ((symbol.row, symbol.col), i))
+            .collect();
+
+        let mut symbol_parts = vec![Vec::new(); symbols.len()];
+        let mut number_symbols = vec![Vec::new(); numbers.len()];
+        for (i, number) in numbers.iter().enumerate() {
+            for row in number.row.saturating_sub(1)..=number.row + 1 {
+                for col in number.start.saturating_sub(1)..=number.end {
+                    if let Some(&symbol) = positions.get(&(row, col)) {
+                        symbol_parts[symbol].push(i);
+                        number_symbols[i].push(symbol);
+                    }
+                }
             }
         }
-    }
 
-    if col > 0 {
-        if let Cell::Gear(x, y) = grid[row][col - 1] {
-            return Some((x, y));
+        Self {
+            numbers,
+            symbols,
+            symbol_parts,
+            number_symbols,
         }
     }
-    if col + 1 < grid[row].len() {
-        if let Cell::Gear(x, y) = grid[row][col + 1] {
-            return Some((x, y));
-        }
+
+    // Every number touching at least one symbol, counted once however many it touches
+    fn part_numbers(&self) -> impl Iterator<Item = &Number> {
+        self.numbers
+            .iter()
+            .zip(&self.number_symbols)
+            .filter(|(_, symbols)| !symbols.is_empty())
+            .map(|(number, _)| number)
     }
 
-    if row + 1 < grid.len() {
-        if col > 0 {
-            if let Cell::Gear(x, y) = grid[row + 1][col - 1] {
-                return Some((x, y));
-            }
-        }
-        if let Cell::Gear(x, y) = grid[row + 1][col] {
-            return Some((x, y));
-        }
-        if col + 1 < grid[row].len() {
-            if let Cell::Gear(x, y) = grid[row + 1][col + 1] {
-                return Some((x, y));
-            }
-        }
+    fn parts(&self, symbol: usize) -> impl Iterator<Item = u32> + '_ {
+        self.symbol_parts[symbol]
+            .iter()
+            .map(|&number| self.numbers[number].value)
     }
 
-    None
+    // `*` symbols touching exactly `count` part numbers
+    fn gears(&self, count: usize) -> impl Iterator<Item = usize> + '_ {
+        (0..self.symbols.len()).filter(move |&symbol| {
+            self.symbols[symbol].char == '*' && self.symbol_parts[symbol].len() == count
+        })
+    }
 }
 
 fn main() {
-    let data = fs::read_to_string("res/day_3.txt").unwrap();
-    let lines: Vec<&str> = data.split('\n').collect();
-
-    let mut grid: Vec<Vec<Cell>> = Vec::with_capacity(lines.len());
-
-    for (i, &line) in lines.iter().enumerate() {
-        let mut row = Vec::with_capacity(line.len());
-        for (j, char) in line.chars().enumerate() {
-            row.push(match char {
-                c if c.is_ascii_digit() => Cell::Digit(c.to_digit(10).unwrap() as u8),
-                '*' => Cell::Gear(i, j),
-                '.' => Cell::Empty,
-                _ => Cell::Symbol,
-            });
+    let data = input::read(3);
+    let schematic = Schematic::parse(&data);
+
+    // Part 2 asks for gears with exactly two parts, but any count can be queried
+    let gear_parts: usize = args::value("gear-parts")
+        .map_or(2, |count| count.parse().expect("Invalid gear part count"));
+
+    let sum_1: u32 = schematic
+        .part_numbers()
+        .map(|number| number.value)
+        .checked_sum();
+    let sum_2: u64 = schematic
+        .gears(gear_parts)
+        .map(|gear| checked::product(schematic.parts(gear).map(u64::from)))
+        .checked_sum();
+
+    println!("Part 1: {sum_1}");
+    println!("Part 2: {sum_2}");
+
+    if args::flag("gears") {
+        println!();
+        println!("Gears with {gear_parts} parts:");
+        for gear in schematic.gears(gear_parts) {
+            let symbol = &schematic.symbols[gear];
+            let parts: Vec<String> = schematic.parts(gear).map(|part| part.to_string()).collect();
+            println!("  ({}, {}): {}", symbol.col, symbol.row, parts.join(", "));
         }
-        grid.push(row);
     }
 
-    let grid = grid;
-
-    let mut sum_1: u32 = 0;
-    let mut numbers: BTreeMap<(usize, usize), Vec<u32>> = BTreeMap::new();
-    let mut is_number = false;
-    let mut current_number: u32 = 0;
-    let mut is_adjacent = false;
-    let mut adjacent_gear: Option<(usize, usize)> = None;
-
-    for (i, row) in grid.iter().enumerate() {
-        for (j, cell) in row.iter().enumerate() {
-            match cell {
-                Cell::Digit(d) => {
-                    if is_number {
-                        current_number = 10 * current_number + *d as u32;
-                    } else {
-                        current_number = *d as u32;
-                    }
-                    is_number = true;
-                    if adjacent_gear.is_none() {
-                        adjacent_gear = is_gear_adjacent(&grid, i, j);
-                    }
-                    is_adjacent =
-                        is_adjacent || adjacent_gear.is_some() || is_symbol_adjacent(&grid, i, j);
-                }
-                _ => {
-                    if is_number {
-                        if let Some(gear_location) = adjacent_gear {
-                            numbers
-                                .entry(gear_location)
-                                .and_modify(|entries: &mut Vec<u32>| entries.push(current_number))
-                                .or_insert(vec![current_number]);
-                        }
-                        if is_adjacent {
-                            sum_1 += current_number;
-                        }
-                        current_number = 0;
-                        is_number = false;
-                        adjacent_gear = None;
-                        is_adjacent = false;
-                    }
-                }
-            }
-        }
-        if is_number {
-            if let Some(gear_location) = adjacent_gear {
-                numbers
-                    .entry(gear_location)
-                    .and_modify(|entries: &mut Vec<u32>| entries.push(current_number))
-                    .or_insert(vec![current_number]);
-            }
-            if is_adjacent {
-                sum_1 += current_number;
-            }
-            current_number = 0;
-            is_number = false;
-            adjacent_gear = None;
-            is_adjacent = false;
+    if args::flag("symbol-sums") {
+        let mut sums: BTreeMap<char, u32> = BTreeMap::new();
+        for (symbol, entity) in schematic.symbols.iter().enumerate() {
+            let sum = sums.entry(entity.char).or_insert(0);
+            *sum = checked::add(*sum, schematic.parts(symbol).checked_sum());
         }
-    }
 
-    let mut sum_2: u32 = 0;
-    for (_, numbers) in numbers.iter() {
-        if numbers.len() > 1 {
-            sum_2 += numbers[0] * numbers[1];
+        println!();
+        println!("Sum of parts per symbol:");
+        for (char, sum) in sums {
+            println!("  {char}: {sum}");
         }
     }
-
-    println!("Part 1: {sum_1}");
-    println!("Part 2: {sum_2}");
 }
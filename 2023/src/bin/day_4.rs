@@ -1,38 +1,54 @@
-use std::{collections::HashSet, fs};
+use advent_of_code_2023::{args, checked, input};
+
+// Card numbers are below 100, so each side of a card fits in one bit per number
+fn parse_numbers(numbers: &str) -> u128 {
+    numbers
+        .split_ascii_whitespace()
+        .map(|number| {
+            let number: u32 = number.parse().unwrap();
+            assert!(number < u128::BITS, "Card number {number} is too large");
+            1u128 << number
+        })
+        .fold(0, |set, bit| set | bit)
+}
 
 fn main() {
-    let data = fs::read_to_string("res/day_4.txt").unwrap();
+    let data = input::read(4);
     let lines: Vec<&str> = data.split('\n').collect();
-    let mut wins = vec![1u32; lines.len()];
+    let mut wins = vec![1u64; lines.len()];
+    let detailed = args::flag("detailed");
 
-    let mut sum_1: u32 = 0;
-    let mut sum_2: u32 = 0;
+    let mut sum_1: u64 = 0;
+    let mut sum_2: u64 = 0;
 
     for (i, line) in lines.iter().enumerate() {
-        let l: Vec<&str> = line.split(':').collect();
-        let l: Vec<&str> = l[1].trim().split('|').collect();
-        let winning = l[0].trim();
-        let numbers = l[1].trim();
-
-        let winning = winning.split_ascii_whitespace();
-        let numbers = numbers.split_ascii_whitespace();
-
-        let winning: HashSet<&str> = HashSet::from_iter(winning);
-        let numbers: HashSet<&str> = HashSet::from_iter(numbers);
-
-        let result: Vec<&&str> = winning.intersection(&numbers).collect();
-        let matches = result.len();
-
-        if matches > 0 {
-            let result = 2u32.pow(result.len() as u32 - 1);
-            sum_1 += result;
+        let (card, numbers) = line.split_once(':').unwrap();
+        let (winning, numbers) = numbers.split_once('|').unwrap();
+
+        let matches = (parse_numbers(winning) & parse_numbers(numbers)).count_ones();
+
+        let points = if matches > 0 {
+            checked::pow(2u64, matches - 1)
+        } else {
+            0
+        };
+        sum_1 = checked::add(sum_1, points);
+
+        // Cards won past the end of the table don't exist
+        let last = (i + matches as usize).min(lines.len() - 1);
+        for j in i + 1..=last {
+            wins[j] = checked::add(wins[j], wins[i]);
         }
 
-        for j in 0..matches {
-            wins[i + 1 + j] += wins[i];
-        }
+        sum_2 = checked::add(sum_2, wins[i]);
 
-        sum_2 += wins[i];
+        if detailed {
+            println!(
+                "{}: {matches} matches, {points} points, {} copies",
+                card.trim(),
+                wins[i]
+            );
+        }
     }
 
     println!("Part 1: {sum_1}");
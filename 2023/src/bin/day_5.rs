@@ -1,4 +1,7 @@
-use std::{fs, thread};
+use advent_of_code_2023::{args, input, parallel};
+
+// Seed ranges are split into chunks of at most this many seeds for the worker pool
+const SEED_CHUNK_SIZE: usize = 1 << 16;
 
 #[derive(Clone, Debug)]
 struct Mapping {
@@ -7,6 +10,21 @@ struct Mapping {
     range_length: usize,
 }
 
+impl Mapping {
+    // The source range is half open, `source_range_start..source_end()`
+    fn source_end(&self) -> usize {
+        self.source_range_start + self.range_length
+    }
+
+    fn contains(&self, value: usize) -> bool {
+        (self.source_range_start..self.source_end()).contains(&value)
+    }
+
+    fn map(&self, value: usize) -> usize {
+        value - self.source_range_start + self.destination_range_start
+    }
+}
+
 fn parse_input(input: &str) -> (Vec<usize>, Vec<Vec<Mapping>>) {
     let mut groups = input.split("\n\n");
 
@@ -38,112 +56,343 @@ fn parse_input(input: &str) -> (Vec<usize>, Vec<Vec<Mapping>>) {
     (seeds, maps)
 }
 
-fn part_1() {
-    let data = fs::read_to_string("res/day_5.txt").unwrap();
-    let (seeds, maps) = parse_input(&data);
+// The map an almanac value goes through, applying the first mapping whose source range holds it.
+// Values outside every mapping keep their number.
+fn map_value(value: usize, map: &[Mapping]) -> usize {
+    map.iter()
+        .find(|mapping| mapping.contains(value))
+        .map_or(value, |mapping| mapping.map(value))
+}
 
-    let mut sources = seeds;
-    let mut destinations = sources.clone();
+fn get_location(seed: usize, maps: &[Vec<Mapping>]) -> usize {
+    maps.iter().fold(seed, |source, map| map_value(source, map))
+}
+
+// Values in `start..end` map to `destination` onwards
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Segment {
+    start: usize,
+    end: usize,
+    destination: usize,
+}
+
+impl Segment {
+    fn map(&self, value: usize) -> usize {
+        value - self.start + self.destination
+    }
+
+    fn destination_end(&self) -> usize {
+        self.map(self.end)
+    }
+}
+
+// A whole map, or a chain of maps composed together, as sorted segments covering every value
+#[derive(Clone, Debug)]
+struct PiecewiseMap {
+    segments: Vec<Segment>,
+}
 
-    for map in maps {
+impl PiecewiseMap {
+    fn identity() -> Self {
+        Self {
+            segments: vec![Segment {
+                start: 0,
+                end: usize::MAX,
+                destination: 0,
+            }],
+        }
+    }
+
+    // Each mapping claims whatever part of its source range earlier mappings haven't, so the
+    // first matching mapping wins, and values nothing claims map to themselves
+    fn from_map(map: &[Mapping]) -> Self {
+        let mut segments = Vec::new();
+        let mut unclaimed = vec![(0, usize::MAX)];
         for mapping in map {
-            for (i, source) in sources.iter().enumerate() {
-                // Check is in range of mapping
-                if *source < mapping.source_range_start
-                    || *source > mapping.source_range_start + mapping.range_length
-                {
-                    continue;
+            let source_start = mapping.source_range_start;
+            let source_end = mapping.source_end();
+
+            let mut remaining = Vec::new();
+            for (start, end) in unclaimed {
+                if start < source_start {
+                    remaining.push((start, end.min(source_start)));
+                }
+                if end > source_end {
+                    remaining.push((start.max(source_end), end));
                 }
 
-                let result = *source as isize
-                    + (mapping.destination_range_start as isize
-                        - mapping.source_range_start as isize);
+                let overlap_start = start.max(source_start);
+                let overlap_end = end.min(source_end);
+                if overlap_start < overlap_end {
+                    segments.push(Segment {
+                        start: overlap_start,
+                        end: overlap_end,
+                        destination: mapping.map(overlap_start),
+                    });
+                }
+            }
+            unclaimed = remaining;
+        }
+
+        segments.extend(unclaimed.into_iter().map(|(start, end)| Segment {
+            start,
+            end,
+            destination: start,
+        }));
+        Self::from_segments(segments)
+    }
 
-                if result < 0 {
-                    continue;
+    // Sorts the segments and merges neighbours that continue the same shift
+    fn from_segments(mut segments: Vec<Segment>) -> Self {
+        segments.retain(|segment| segment.start < segment.end);
+        segments.sort_unstable_by_key(|segment| segment.start);
+
+        let mut merged: Vec<Segment> = Vec::with_capacity(segments.len());
+        for segment in segments {
+            match merged.last_mut() {
+                Some(last)
+                    if last.end == segment.start
+                        && last.destination_end() == segment.destination =>
+                {
+                    last.end = segment.end;
                 }
+                _ => merged.push(segment),
+            }
+        }
+        Self { segments: merged }
+    }
 
-                destinations[i] = result as usize;
+    // The segments overlapping `start..end`
+    fn overlapping(&self, start: usize, end: usize) -> impl Iterator<Item = &Segment> {
+        let first = self
+            .segments
+            .partition_point(|segment| segment.end <= start);
+        self.segments[first..]
+            .iter()
+            .take_while(move |segment| segment.start < end)
+    }
+
+    fn apply(&self, value: usize) -> usize {
+        self.overlapping(value, value + 1)
+            .next()
+            .unwrap()
+            .map(value)
+    }
+
+    // Applies `self`, then `next`
+    fn then(&self, next: &PiecewiseMap) -> Self {
+        let mut segments = Vec::new();
+        for segment in &self.segments {
+            for target in next.overlapping(segment.destination, segment.destination_end()) {
+                let from = segment.destination.max(target.start);
+                let to = segment.destination_end().min(target.end);
+                segments.push(Segment {
+                    start: from - segment.destination + segment.start,
+                    end: to - segment.destination + segment.start,
+                    destination: target.map(from),
+                });
             }
         }
+        Self::from_segments(segments)
+    }
 
-        sources = destinations.clone();
+    // Where the values in `start..end` end up, as sorted and merged ranges
+    fn image(&self, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let ranges = self
+            .overlapping(start, end)
+            .map(|segment| {
+                let from = segment.start.max(start);
+                let to = segment.end.min(end);
+                (segment.map(from), segment.map(to))
+            })
+            .collect();
+        merge_ranges(ranges)
     }
 
-    let min = sources.iter().min().unwrap();
+    // Every value that ends up in `start..end`. Maps aren't always one to one, so this can be
+    // several ranges, or none at all.
+    fn preimage(&self, start: usize, end: usize) -> Vec<(usize, usize)> {
+        let ranges = self
+            .segments
+            .iter()
+            .filter_map(|segment| {
+                let from = segment.destination.max(start);
+                let to = segment.destination_end().min(end);
+                (from < to).then(|| {
+                    (
+                        from - segment.destination + segment.start,
+                        to - segment.destination + segment.start,
+                    )
+                })
+            })
+            .collect();
+        merge_ranges(ranges)
+    }
+}
 
-    println!("Part 1: {min}");
+fn merge_ranges(mut ranges: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    ranges.sort_unstable();
+    let mut merged: Vec<(usize, usize)> = Vec::with_capacity(ranges.len());
+    for (start, end) in ranges {
+        match merged.last_mut() {
+            Some(last) if last.1 >= start => last.1 = last.1.max(end),
+            _ => merged.push((start, end)),
+        }
+    }
+    merged
 }
 
-fn part_2() {
-    let data = fs::read_to_string("res/day_5.txt").unwrap();
-    let (seeds, maps) = parse_input(&data);
+fn compose(maps: &[Vec<Mapping>]) -> PiecewiseMap {
+    maps.iter().fold(PiecewiseMap::identity(), |composed, map| {
+        composed.then(&PiecewiseMap::from_map(map))
+    })
+}
 
-    let mut seed_ranges: Vec<(usize, usize)> = Vec::new();
-    let mut current_start = None;
-    for seed in seeds {
-        if current_start.is_none() {
-            current_start = Some(seed);
-        } else {
-            seed_ranges.push((current_start.unwrap(), seed));
-            current_start = None;
+// Either a single value or a half open `start..end` range
+fn parse_query(query: &str) -> (usize, usize) {
+    let parse = |value: &str| -> usize {
+        value
+            .trim()
+            .parse()
+            .unwrap_or_else(|_| panic!("Invalid query: {query}"))
+    };
+    match query.split_once("..") {
+        Some((start, end)) => (parse(start), parse(end)),
+        None => {
+            let value = parse(query);
+            (value, value + 1)
         }
     }
+}
 
-    let mut threads = Vec::new();
-
-    for seed_range in seed_ranges {
-        let maps = maps.clone();
-        let result = thread::spawn(move || {
-            (seed_range.0..(seed_range.0 + seed_range.1))
-                .map(|seed| {
-                    let mut source: usize = seed;
-                    let mut destination = usize::MAX;
-                    for map in maps.iter() {
-                        for mapping in map.iter() {
-                            if source < mapping.source_range_start
-                                || source > mapping.source_range_start + mapping.range_length
-                            {
-                                continue;
-                            }
-
-                            let result = source as isize
-                                + (mapping.destination_range_start as isize
-                                    - mapping.source_range_start as isize);
-                            if result < 0 {
-                                continue;
-                            }
-
-                            let result = result as usize;
-                            if result < destination {
-                                destination = result;
-                            }
-                        }
-
-                        if destination != usize::MAX {
-                            source = destination;
-                            destination = usize::MAX;
-                        }
-                    }
+fn format_ranges(ranges: &[(usize, usize)]) -> String {
+    if ranges.is_empty() {
+        return String::from("nothing");
+    }
+    ranges
+        .iter()
+        .map(|(start, end)| format!("{start}..{end}"))
+        .collect::<Vec<String>>()
+        .join(", ")
+}
 
-                    source
-                })
+fn part_1() {
+    let data = input::read(5);
+    let (seeds, maps) = parse_input(&data);
+
+    let min = if args::flag("brute-force") {
+        seeds
+            .into_iter()
+            .map(|seed| get_location(seed, &maps))
+            .min()
+    } else {
+        let composed = compose(&maps);
+        seeds.into_iter().map(|seed| composed.apply(seed)).min()
+    }
+    .unwrap();
+
+    println!("Part 1: {min}");
+}
+
+// Maps every single seed, which is far too slow for the real input but is a useful reference for
+// the interval solver on small almanacs
+fn brute_force(seed_ranges: &[(usize, usize)], maps: &[Vec<Mapping>]) -> usize {
+    let mut seed_chunks: Vec<(usize, usize)> = Vec::new();
+    for &(start, end) in seed_ranges {
+        let mut chunk_start = start;
+        while chunk_start < end {
+            let chunk_end = end.min(chunk_start + SEED_CHUNK_SIZE);
+            seed_chunks.push((chunk_start, chunk_end));
+            chunk_start = chunk_end;
+        }
+    }
+
+    parallel::map_reduce(
+        &seed_chunks,
+        |&(start, end)| {
+            (start..end)
+                .map(|seed| get_location(seed, maps))
                 .min()
                 .unwrap()
-        });
-        threads.push(result);
-    }
+        },
+        usize::min,
+    )
+    .unwrap()
+}
+
+fn part_2() {
+    let data = input::read(5);
+    let (seeds, maps) = parse_input(&data);
+
+    let seed_ranges: Vec<(usize, usize)> = seeds
+        .chunks_exact(2)
+        .map(|pair| (pair[0], pair[0] + pair[1]))
+        .filter(|(start, end)| start < end)
+        .collect();
 
-    let min = threads
-        .into_iter()
-        .map(|thread| thread.join().unwrap())
-        .min()
-        .unwrap();
+    let min = if args::flag("brute-force") {
+        brute_force(&seed_ranges, &maps)
+    } else {
+        let composed = compose(&maps);
+        seed_ranges
+            .iter()
+            .flat_map(|&(start, end)| composed.image(start, end))
+            .map(|(start, _)| start)
+            .min()
+            .unwrap()
+    };
 
     println!("Part 2: {min}");
 }
 
+// Answers `--forward` (seeds to locations) and `--backward` (locations to seeds) queries for a
+// value or a range, and dumps the composed seed to location map with `--segments`
+fn queries() {
+    let forward = args::value("forward");
+    let backward = args::value("backward");
+    let segments = args::flag("segments");
+    if forward.is_none() && backward.is_none() && !segments {
+        return;
+    }
+
+    let data = input::read(5);
+    let (_, maps) = parse_input(&data);
+    let composed = compose(&maps);
+
+    if let Some(query) = forward {
+        let (start, end) = parse_query(&query);
+        println!(
+            "Seeds {start}..{end} -> locations {}",
+            format_ranges(&composed.image(start, end))
+        );
+    }
+    if let Some(query) = backward {
+        let (start, end) = parse_query(&query);
+        println!(
+            "Locations {start}..{end} <- seeds {}",
+            format_ranges(&composed.preimage(start, end))
+        );
+    }
+
+    if segments {
+        println!(
+            "{:>20} {:>20} {:>20} {:>20}",
+            "seed start", "seed end", "location start", "location end"
+        );
+        for segment in &composed.segments {
+            println!(
+                "{:>20} {:>20} {:>20} {:>20}",
+                segment.start,
+                segment.end,
+                segment.destination,
+                segment.destination_end()
+            );
+        }
+    }
+}
+
 fn main() {
     part_1();
     part_2();
+    queries();
 }
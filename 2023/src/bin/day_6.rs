@@ -1,79 +1,114 @@
-use std::fs;
+use std::process;
+
+use advent_of_code_2023::{args, checked::CheckedIterator, input};
+
+// Holding the button for `hold` leaves `time - hold` to travel at `hold` per millisecond. The
+// distance itself can overflow for huge races, so `hold * (time - hold) > record` is checked as
+// `time - hold > record / hold`, which is the same for integers.
+fn beats(hold: u128, time: u128, record: u128) -> bool {
+    hold > 0 && time - hold > record / hold
+}
+
+// Counts the hold times that travel strictly further than `record`. The distances form a
+// parabola symmetric around `time / 2`, so only its first winning hold time has to be found: the
+// integer square root gets within one of it, and equal distances don't count as wins.
+fn ways_to_win(time: u128, record: u128) -> u128 {
+    if !beats(time / 2, time, record) {
+        return 0;
+    }
+
+    // The real roots are (time ± sqrt(time² - 4 * record)) / 2, and the discriminant is positive
+    // since the best hold time beats the record. When time² doesn't fit in 128 bits, a binary
+    // search over the rising half of the parabola finds the first win instead.
+    let discriminant = time
+        .checked_mul(time)
+        .zip(record.checked_mul(4))
+        .map(|(square, record)| square - record);
+    let mut first = match discriminant {
+        Some(discriminant) => (time - discriminant.isqrt()) / 2,
+        None => {
+            let (mut low, mut high) = (0, time / 2);
+            while low < high {
+                let middle = low + (high - low) / 2;
+                if beats(middle, time, record) {
+                    high = middle;
+                } else {
+                    low = middle + 1;
+                }
+            }
+            low
+        }
+    };
+    while !beats(first, time, record) {
+        first += 1;
+    }
+    while first > 0 && beats(first - 1, time, record) {
+        first -= 1;
+    }
+
+    let last = time - first;
+    last - first + 1
+}
+
+fn ways_to_win_brute_force(time: u128, record: u128) -> u128 {
+    (0..=time).filter(|&hold| beats(hold, time, record)).count() as u128
+}
+
+fn parse_number(number: &str) -> u128 {
+    number.parse().unwrap_or_else(|error| {
+        eprintln!("Invalid race number {number}: {error}");
+        process::exit(1);
+    })
+}
+
+fn count_wins(time: u128, record: u128) -> u128 {
+    if args::flag("brute-force") {
+        ways_to_win_brute_force(time, record)
+    } else {
+        ways_to_win(time, record)
+    }
+}
 
 fn part_1() {
-    let data = fs::read_to_string("res/day_6.txt").unwrap();
+    let data = input::read(6);
     let mut lines = data.split('\n');
-    let times: Vec<u32> = lines
+    let times: Vec<u128> = lines
         .next()
         .unwrap()
         .replace("Time:", "")
         .trim()
         .split_ascii_whitespace()
-        .map(|time| time.parse().unwrap())
+        .map(parse_number)
         .collect();
-    let distances: Vec<u32> = lines
+    let distances: Vec<u128> = lines
         .next()
         .unwrap()
         .replace("Distance:", "")
         .trim()
         .split_ascii_whitespace()
-        .map(|time| time.parse().unwrap())
+        .map(parse_number)
         .collect();
 
-    let accumulator: usize = times
+    let accumulator: u128 = times
         .into_iter()
-        .enumerate()
-        .map(|(i, time)| {
-            let distance = distances[i];
-
-            let a = -1f64;
-            let b = time as f64;
-            let c = -(distance as f64);
-
-            let x1 = (-b + (b.powf(2.0) - 4.0 * a * c).sqrt()) / 2.0 * a;
-            let x2 = (-b - (b.powf(2.0) - 4.0 * a * c).sqrt()) / 2.0 * a;
-
-            let x1 = x1.ceil() as usize;
-            let x2 = x2.ceil() as usize;
-
-            x2 - x1
-        })
-        .product();
+        .zip(distances)
+        .map(|(time, distance)| count_wins(time, distance))
+        .checked_product();
 
     println!("Part 1: {accumulator}");
 }
 
 fn part_2() {
-    let data = fs::read_to_string("res/day_6.txt").unwrap();
+    let data = input::read(6);
     let mut lines = data.split('\n');
-    let time: usize = lines
-        .next()
-        .unwrap()
-        .replace("Time:", "")
-        .replace(' ', "")
-        .parse()
-        .unwrap();
-    let distance: usize = lines
+    let time = lines.next().unwrap().replace("Time:", "").replace(' ', "");
+    let distance = lines
         .next()
         .unwrap()
         .replace("Distance:", "")
-        .replace(' ', "")
-        .parse()
-        .unwrap();
-
-    let mut wins = 0;
+        .replace(' ', "");
 
-    let a = -1f64;
-    let b = time as f64;
-    let c = -(distance as f64 + 1.0);
-
-    let x1 = (-b + (b.powf(2.0) - 4.0 * a * c).sqrt()) / 2.0 * a;
-    let x2 = (-b - (b.powf(2.0) - 4.0 * a * c).sqrt()) / 2.0 * a;
-
-    let x1 = x1.ceil() as usize;
-    let x2 = x2.ceil() as usize;
-
-    wins += x2 - x1;
+    let wins = count_wins(parse_number(&time), parse_number(&distance));
 
     println!("Part 2: {wins}");
 }
@@ -82,3 +117,39 @@ fn main() {
     part_1();
     part_2();
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn small_races_match_brute_force() {
+        for time in 0..60 {
+            for record in 0..time * time / 4 + 2 {
+                assert_eq!(
+                    ways_to_win(time, record),
+                    ways_to_win_brute_force(time, record),
+                    "time {time}, record {record}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn large_races() {
+        // Wins are the hold times within 2 of 2^62, whose distances are 2^124 minus a square
+        assert_eq!(ways_to_win(1 << 63, (1 << 124) - 5), 5);
+        // time² no longer fits in 128 bits, and only holding for exactly half the race wins
+        assert_eq!(ways_to_win(1 << 65, u128::MAX), 1);
+        assert_eq!(
+            ways_to_win(10_u128.pow(25), 10_u128.pow(38)),
+            9_999_999_999_979_999_999_999_979
+        );
+        // Every hold time but the two ends travels some distance
+        assert_eq!(ways_to_win(u128::MAX, 0), u128::MAX - 1);
+        assert_eq!(
+            ways_to_win(12_345_678_901_234_567_890, 98_765_432_109_876_543_210),
+            12_345_678_901_234_567_873
+        );
+    }
+}
@@ -1,159 +1,496 @@
-use std::{collections::BTreeMap, fs, str};
+use std::{
+    collections::{BTreeSet, HashMap},
+    fmt::Write as _,
+};
+
+use advent_of_code_2023::{args, checked, input};
+use lazy_regex::{regex, Regex};
+
+// Hands out dense indices to node names in the order they're first seen
+#[derive(Debug, Default)]
+struct Interner {
+    indices: HashMap<String, u32>,
+    names: Vec<String>,
+}
+
+impl Interner {
+    fn intern(&mut self, name: &str) -> u32 {
+        if let Some(&index) = self.indices.get(name) {
+            return index;
+        }
+        let index = self.names.len() as u32;
+        self.indices.insert(name.to_string(), index);
+        self.names.push(name.to_string());
+        index
+    }
+
+    fn get(&self, name: &str) -> Option<u32> {
+        self.indices.get(name).copied()
+    }
+
+    fn name(&self, index: u32) -> &str {
+        &self.names[index as usize]
+    }
+
+    fn len(&self) -> usize {
+        self.names.len()
+    }
+}
+
+// Marks a node that edges lead to but that never had its own line
+const UNDEFINED: [u32; 2] = [u32::MAX; 2];
 
 #[derive(Debug)]
-struct Node {
-    name: usize,
-    directions: [usize; 2],
+struct Network {
+    instructions: Vec<u8>,
+    nodes: Interner,
+    // The left and right neighbours of every node, indexed by node
+    edges: Vec<[u32; 2]>,
 }
 
-impl From<&str> for Node {
-    fn from(value: &str) -> Self {
-        let name = value[0..3].as_bytes();
-        let left = value[7..10].as_bytes();
-        let right = value[12..15].as_bytes();
+impl Network {
+    // Parses the instructions and the network, making sure every edge leads to a node that
+    // exists so the walks below never have to deal with dangling references
+    fn parse(data: &str) -> Self {
+        let node_re = regex!(
+            r"^(?<name>[^\s=]+)\s*=\s*\(\s*(?<left>[^\s,()]+)\s*,\s*(?<right>[^\s,()]+)\s*\)$"
+        );
+
+        let mut sections = data.split("\n\n");
+
+        let instructions: Vec<u8> = sections
+            .next()
+            .unwrap()
+            .trim()
+            .chars()
+            .map(|char| match char {
+                'L' => 0u8,
+                'R' => 1u8,
+                _ => panic!("Invalid direction"),
+            })
+            .collect();
+
+        let mut nodes = Interner::default();
+        let mut edges = Vec::new();
+        for line in sections.next().unwrap().split('\n').map(str::trim) {
+            if line.is_empty() {
+                continue;
+            }
+            let captures = node_re
+                .captures(line)
+                .unwrap_or_else(|| panic!("Invalid node: {line}"));
+            let name = nodes.intern(&captures["name"]);
+            let left = nodes.intern(&captures["left"]);
+            let right = nodes.intern(&captures["right"]);
+
+            edges.resize(nodes.len(), UNDEFINED);
+            edges[name as usize] = [left, right];
+        }
 
-        let name = (name[0] as usize) << 16 | (name[1] as usize) << 8 | (name[2] as usize);
-        let left = (left[0] as usize) << 16 | (left[1] as usize) << 8 | (left[2] as usize);
-        let right = (right[0] as usize) << 16 | (right[1] as usize) << 8 | (right[2] as usize);
+        let missing: BTreeSet<&str> = edges
+            .iter()
+            .enumerate()
+            .filter(|(_, edges)| **edges == UNDEFINED)
+            .map(|(node, _)| nodes.name(node as u32))
+            .collect();
+        if !missing.is_empty() {
+            let missing: Vec<&str> = missing.into_iter().collect();
+            panic!("Edges lead to missing nodes: {}", missing.join(", "));
+        }
 
         Self {
-            name,
-            directions: [left, right],
+            instructions,
+            nodes,
+            edges,
+        }
+    }
+
+    fn is_start(&self, node: u32) -> bool {
+        self.nodes.name(node).ends_with('A')
+    }
+
+    fn is_end(&self, node: u32) -> bool {
+        self.nodes.name(node).ends_with('Z')
+    }
+
+    // The network as a Graphviz digraph, with start nodes in green and end nodes in red
+    fn to_dot(&self) -> String {
+        let mut dot = String::from("digraph network {\n");
+        for node in 0..self.nodes.len() as u32 {
+            let colour = if self.is_start(node) {
+                Some("green")
+            } else if self.is_end(node) {
+                Some("red")
+            } else {
+                None
+            };
+            match colour {
+                Some(colour) => writeln!(
+                    dot,
+                    "  {:?} [style=filled, fillcolor={colour}];",
+                    self.nodes.name(node)
+                ),
+                None => writeln!(dot, "  {:?};", self.nodes.name(node)),
+            }
+            .unwrap();
         }
+        for (node, [left, right]) in self.edges.iter().enumerate() {
+            let name = self.nodes.name(node as u32);
+            if left == right {
+                writeln!(
+                    dot,
+                    "  {name:?} -> {:?} [label=\"LR\"];",
+                    self.nodes.name(*left)
+                )
+                .unwrap();
+                continue;
+            }
+            writeln!(
+                dot,
+                "  {name:?} -> {:?} [label=\"L\"];",
+                self.nodes.name(*left)
+            )
+            .unwrap();
+            writeln!(
+                dot,
+                "  {name:?} -> {:?} [label=\"R\"];",
+                self.nodes.name(*right)
+            )
+            .unwrap();
+        }
+        dot.push('}');
+        dot
     }
 }
 
-#[allow(dead_code)]
-fn to_chars(input: usize) -> String {
-    format!(
-        "{}{}{}",
-        (input >> 16) as u8 as char,
-        (input >> 8 & 0xFF) as u8 as char,
-        (input & 0xFF) as u8 as char
-    )
+// One walk through the network. Its (node, instruction index) states must eventually repeat, so
+// the steps it spends on target nodes are a few before the cycle starts and then the same offsets
+// within every pass around the cycle, and the nodes seen by then are all it can ever reach.
+#[derive(Debug)]
+struct Trajectory {
+    cycle_start: u128,
+    cycle_length: u128,
+    // Steps on a target node before the cycle starts, and during its first pass, in increasing
+    // order
+    lead_in_hits: Vec<u128>,
+    cycle_hits: Vec<u128>,
+    visited: BTreeSet<u32>,
 }
 
-fn gcd(a: usize, b: usize) -> usize {
-    if a == 0 {
-        return b;
+impl Trajectory {
+    fn new(network: &Network, start: u32, is_target: impl Fn(u32) -> bool) -> Self {
+        let instructions = &network.instructions;
+        let mut seen: HashMap<(u32, usize), u128> = HashMap::new();
+        let mut hits = Vec::new();
+        let mut location = start;
+        let mut instruction_index = 0;
+        let mut steps: u128 = 0;
+
+        let cycle_start = loop {
+            if let Some(&first) = seen.get(&(location, instruction_index)) {
+                break first;
+            }
+            seen.insert((location, instruction_index), steps);
+            if is_target(location) {
+                hits.push(steps);
+            }
+
+            location = network.edges[location as usize][instructions[instruction_index] as usize];
+            instruction_index = (instruction_index + 1) % instructions.len();
+            steps += 1;
+        };
+
+        let cycle_hits = hits.split_off(hits.partition_point(|hit| *hit < cycle_start));
+        Self {
+            cycle_start,
+            cycle_length: steps - cycle_start,
+            lead_in_hits: hits,
+            cycle_hits,
+            visited: seen.into_keys().map(|(node, _)| node).collect(),
+        }
+    }
+
+    fn first_hit(&self) -> Option<u128> {
+        self.lead_in_hits
+            .first()
+            .or(self.cycle_hits.first())
+            .copied()
+    }
+
+    fn is_on_end(&self, steps: u128) -> bool {
+        if steps < self.cycle_start {
+            return self.lead_in_hits.binary_search(&steps).is_ok();
+        }
+        let offset = self.cycle_start + (steps - self.cycle_start) % self.cycle_length;
+        self.cycle_hits.binary_search(&offset).is_ok()
     }
-    gcd(b % a, a)
 }
 
-fn lcm(input: Vec<usize>) -> usize {
-    let mut result = input[0];
+// Returns `(gcd, x, y)` with `a * x + b * y == gcd`
+fn extended_gcd(a: i128, b: i128) -> (i128, i128, i128) {
+    if b == 0 {
+        return (a, 1, 0);
+    }
+    let (gcd, x, y) = extended_gcd(b, a % b);
+    (gcd, y, x - (a / b) * y)
+}
 
-    for i in input {
-        result = (i * result) / gcd(i, result);
+// Combines `x ≡ a (mod m)` and `x ≡ b (mod n)` into one congruence, if both can hold. The moduli
+// don't have to be coprime.
+fn crt((a, m): (u128, u128), (b, n): (u128, u128)) -> Option<(u128, u128)> {
+    let (gcd, p, _) = extended_gcd(m as i128, n as i128);
+    let difference = b as i128 - a as i128;
+    if difference % gcd != 0 {
+        return None;
     }
 
-    result
+    // m * k ≡ b - a (mod n), and p is the inverse of m / gcd modulo n / gcd
+    let step = n as i128 / gcd;
+    let k = ((difference / gcd).rem_euclid(step) * p.rem_euclid(step)).rem_euclid(step);
+    let lcm = checked::mul(m / gcd as u128, n);
+    Some(((a + checked::mul(m, k as u128)) % lcm, lcm))
 }
 
-fn part_1() {
-    let data = fs::read_to_string("res/day_8.txt").unwrap();
-    let mut sections = data.split("\n\n");
-
-    let instructions: Vec<u8> = sections
-        .next()
-        .unwrap()
-        .chars()
-        .map(|char| match char {
-            'L' => 0u8,
-            'R' => 1u8,
-            _ => panic!("Invalid direction"),
-        })
-        .collect();
+// The first step at which every ghost is on a Z node, if there is one
+fn earliest_meeting(trajectories: &[Trajectory]) -> Option<u128> {
+    // Until every ghost has reached its cycle, check the steps one by one
+    let settled = trajectories
+        .iter()
+        .map(|trajectory| trajectory.cycle_start)
+        .max()?;
+    if let Some(steps) = (0..settled).find(|steps| {
+        trajectories
+            .iter()
+            .all(|trajectory| trajectory.is_on_end(*steps))
+    }) {
+        return Some(steps);
+    }
 
-    let nodes = sections
-        .next()
-        .unwrap()
-        .split('\n')
-        .map(Node::from)
-        .map(|node| (node.name, node.directions));
-
-    let map = BTreeMap::from_iter(nodes);
-
-    let starting_location = ('A' as usize) << 16 | ('A' as usize) << 8 | ('A' as usize);
-    let ending_location = ('Z' as usize) << 16 | ('Z' as usize) << 8 | ('Z' as usize);
-
-    let mut instruction_index: usize = 0;
-    let mut current_location = starting_location;
-    let mut steps: usize = 0;
-    while current_location != ending_location {
-        current_location =
-            map.get(&current_location).unwrap()[instructions[instruction_index] as usize];
-        instruction_index += 1;
-        if instruction_index == instructions.len() {
-            instruction_index = 0;
-        }
-        steps += 1;
+    // From then on a ghost is on a Z node exactly when the step matches one of its cycle hits
+    // modulo its cycle length, so every way of picking one hit per ghost is a system of
+    // congruences
+    let mut congruences = vec![(0u128, 1u128)];
+    for trajectory in trajectories {
+        let mut combined: Vec<(u128, u128)> = congruences
+            .iter()
+            .flat_map(|&congruence| {
+                trajectory.cycle_hits.iter().filter_map(move |hit| {
+                    crt(
+                        congruence,
+                        (hit % trajectory.cycle_length, trajectory.cycle_length),
+                    )
+                })
+            })
+            .collect();
+        combined.sort_unstable();
+        combined.dedup();
+        congruences = combined;
     }
 
-    println!("Part 1: {steps}");
+    congruences
+        .into_iter()
+        .map(|(residue, modulus)| {
+            if residue >= settled {
+                residue
+            } else {
+                residue + (settled - residue).div_ceil(modulus) * modulus
+            }
+        })
+        .min()
 }
 
-fn part_2() {
-    let data = fs::read_to_string("res/day_8.txt").unwrap();
-    let mut sections = data.split("\n\n");
-
-    let instructions: Vec<u8> = sections
-        .next()
-        .unwrap()
-        .chars()
-        .map(|char| match char {
-            'L' => 0u8,
-            'R' => 1u8,
-            _ => panic!("Invalid direction"),
-        })
-        .collect();
+// Binary lifting over the combined (node, instruction index) states, numbered
+// `node * instructions + index`. Level k holds where each state is after 2^k steps, and whether any
+// of those steps lands on a target node, so walks of N steps take O(log N) jumps.
+struct JumpTable {
+    instruction_count: usize,
+    jumps: Vec<Vec<u32>>,
+    hits: Vec<Vec<bool>>,
+}
 
-    let nodes = sections
-        .next()
-        .unwrap()
-        .split('\n')
-        .map(Node::from)
-        .map(|node| (node.name, node.directions));
+impl JumpTable {
+    // Builds enough levels to cover walks of up to `max_steps`
+    fn new(network: &Network, max_steps: u64, is_target: impl Fn(u32) -> bool) -> Self {
+        let instruction_count = network.instructions.len();
+        let states = network.nodes.len() * instruction_count;
+        assert!(
+            states <= u32::MAX as usize,
+            "Too many states for a jump table"
+        );
 
-    let nodes: Vec<(usize, [usize; 2])> = nodes.collect();
+        let (first_jumps, first_hits): (Vec<u32>, Vec<bool>) = (0..states)
+            .map(|state| {
+                let (node, index) = (state / instruction_count, state % instruction_count);
+                let next = network.edges[node][network.instructions[index] as usize];
+                let next_state =
+                    next as usize * instruction_count + (index + 1) % instruction_count;
+                (next_state as u32, is_target(next))
+            })
+            .unzip();
 
-    let mut starting_nodes = Vec::new();
-    for (name, _) in nodes.iter() {
-        if name & 0xFF == ('A' as usize) {
-            starting_nodes.push(*name);
+        let mut jumps = vec![first_jumps];
+        let mut hits = vec![first_hits];
+        let levels = (u64::BITS - max_steps.leading_zeros()).max(1);
+        for level in 1..levels as usize {
+            let (previous_jumps, previous_hits) = (&jumps[level - 1], &hits[level - 1]);
+            let (next_jumps, next_hits) = (0..states)
+                .map(|state| {
+                    let middle = previous_jumps[state] as usize;
+                    (
+                        previous_jumps[middle],
+                        previous_hits[state] || previous_hits[middle],
+                    )
+                })
+                .unzip();
+            jumps.push(next_jumps);
+            hits.push(next_hits);
         }
-    }
 
-    let map = BTreeMap::from_iter(nodes);
+        Self {
+            instruction_count,
+            jumps,
+            hits,
+        }
+    }
 
-    let mut instruction_index: usize = 0;
-    let mut steps: usize = 0;
+    fn start(&self, node: u32) -> usize {
+        node as usize * self.instruction_count
+    }
 
-    let mut current_locations = starting_nodes.clone();
-    let mut finished = vec![0; current_locations.len()];
+    fn node(&self, state: usize) -> u32 {
+        (state / self.instruction_count) as u32
+    }
 
-    while finished.iter().any(|f| *f == 0) {
-        for i in 0..current_locations.len() {
-            if finished[i] != 0 {
-                continue;
+    fn after(&self, mut state: usize, steps: u64) -> usize {
+        for (level, jumps) in self.jumps.iter().enumerate() {
+            if steps >> level & 1 == 1 {
+                state = jumps[state] as usize;
             }
-            current_locations[i] =
-                map.get(&current_locations[i]).unwrap()[instructions[instruction_index] as usize];
-            if current_locations[i] & ('Z' as usize) == 'Z' as usize {
-                finished[i] = steps + 1;
+        }
+        state
+    }
+
+    // The first step within `steps` that lands on a target node. Jumps over every block that
+    // contains no hit, largest first, so the step after the last jump is the first hit.
+    fn first_hit(&self, mut state: usize, steps: u64) -> Option<u64> {
+        let mut taken = 0;
+        for level in (0..self.jumps.len()).rev() {
+            let block = 1 << level;
+            if block <= steps - taken && !self.hits[level][state] {
+                state = self.jumps[level][state] as usize;
+                taken += block;
             }
         }
-        instruction_index += 1;
-        if instruction_index == instructions.len() {
-            instruction_index = 0;
+        (taken < steps).then_some(taken + 1)
+    }
+}
+
+// Answers `--after N` (where a walker from `--from`, AAA by default, is after N steps) and
+// `--within N` (the first step within N that lands on a node matching the `--target` regex, ZZZ
+// by default)
+fn queries() -> bool {
+    let after = args::value("after").map(|steps| steps.parse::<u64>().expect("Invalid step count"));
+    let within =
+        args::value("within").map(|steps| steps.parse::<u64>().expect("Invalid step count"));
+    if after.is_none() && within.is_none() {
+        return false;
+    }
+
+    let data = input::read(8);
+    let network = Network::parse(&data);
+
+    let from = args::value("from").unwrap_or_else(|| String::from("AAA"));
+    let start = network
+        .nodes
+        .get(&from)
+        .unwrap_or_else(|| panic!("No node named {from}"));
+    let target = args::value("target").unwrap_or_else(|| String::from("^ZZZ$"));
+    let target_re = Regex::new(&target).expect("Invalid target pattern");
+
+    let max_steps = after.unwrap_or(0).max(within.unwrap_or(0));
+    let table = JumpTable::new(&network, max_steps, |node| {
+        target_re.is_match(network.nodes.name(node))
+    });
+
+    if let Some(steps) = after {
+        let state = table.after(table.start(start), steps);
+        println!(
+            "After {steps} steps from {from}: {} (instruction {})",
+            network.nodes.name(table.node(state)),
+            state % table.instruction_count
+        );
+    }
+    if let Some(steps) = within {
+        // Standing on a target before moving counts as reaching it at step 0
+        let hit = if target_re.is_match(&from) {
+            Some(0)
+        } else {
+            table.first_hit(table.start(start), steps)
+        };
+        match hit {
+            Some(hit) => println!(
+                "First {target} node within {steps} steps from {from}: step {hit} ({})",
+                network
+                    .nodes
+                    .name(table.node(table.after(table.start(start), hit)))
+            ),
+            None => println!("No {target} node within {steps} steps from {from}"),
         }
-        steps += 1;
     }
 
-    println!("Part 2: {}", lcm(finished));
+    true
+}
+
+fn part_1() {
+    let data = input::read(8);
+    let network = Network::parse(&data);
+
+    let (Some(start), end) = (network.nodes.get("AAA"), network.nodes.get("ZZZ")) else {
+        println!("Part 1: unreachable, there is no AAA node");
+        return;
+    };
+
+    // Walking until the state repeats either finds ZZZ or shows it can never be reached
+    let trajectory = Trajectory::new(&network, start, |node| Some(node) == end);
+    match trajectory.first_hit() {
+        Some(steps) => println!("Part 1: {steps}"),
+        None => {
+            let mut reachable: Vec<&str> = trajectory
+                .visited
+                .into_iter()
+                .map(|node| network.nodes.name(node))
+                .collect();
+            reachable.sort_unstable();
+            println!("Part 1: unreachable");
+            println!("  Nodes reachable from AAA: {}", reachable.join(", "));
+        }
+    }
+}
+
+fn part_2() {
+    let data = input::read(8);
+    let network = Network::parse(&data);
+
+    let trajectories: Vec<Trajectory> = (0..network.nodes.len() as u32)
+        .filter(|node| network.is_start(*node))
+        .map(|start| Trajectory::new(&network, start, |node| network.is_end(node)))
+        .collect();
+
+    match earliest_meeting(&trajectories) {
+        Some(steps) => println!("Part 2: {steps}"),
+        None => println!("Part 2: the ghosts are never all on Z nodes at once"),
+    }
 }
 
 fn main() {
+    if args::flag("dot") {
+        println!("{}", Network::parse(&input::read(8)).to_dot());
+        return;
+    }
+    if queries() {
+        return;
+    }
+
     part_1();
     part_2();
 }
@@ -1,59 +1,144 @@
-use std::fs;
+use std::process;
 
-fn get_diffs(input: &Vec<isize>) -> Vec<isize> {
-    let mut output = Vec::with_capacity(input.len() - 1);
-    for i in 0..input.len() - 1 {
-        output.push(input[i + 1] - input[i]);
-    }
-    output
-}
+use advent_of_code_2023::{args, checked, checked::CheckedIterator, input};
 
-fn part_1() {
-    let data = fs::read_to_string("res/day_9.txt").unwrap();
-    let lines = data.split('\n');
+// A sequence in Newton forward difference form: the value at position x (0 being the first
+// reading) is the sum of `coefficients[j] * C(x, j)`, where coefficient j is the first entry of
+// the j-th row of differences. The degree is the number of coefficients minus one.
+#[derive(Debug)]
+struct Polynomial {
+    coefficients: Vec<i128>,
+}
 
-    let result: isize = lines
-        .map(|line| {
-            let reading = line
-                .split_ascii_whitespace()
-                .map(|reading| str::parse(reading).unwrap())
+impl Polynomial {
+    // Fails if the differences never reach a row of zeros, since then no polynomial of lower
+    // degree than the number of readings is confirmed by them
+    fn fit(readings: &[i128]) -> Result<Self, String> {
+        let mut coefficients = Vec::new();
+        let mut row = readings.to_vec();
+        while !row.is_empty() {
+            if row.iter().all(|value| *value == 0) {
+                return Ok(Self { coefficients });
+            }
+            coefficients.push(row[0]);
+            row = row
+                .windows(2)
+                .map(|pair| checked::sub(pair[1], pair[0]))
                 .collect();
-            let mut differences: Vec<Vec<isize>> = vec![reading];
-            while differences.is_empty() || differences.last().unwrap().iter().any(|d| *d != 0) {
-                differences.push(get_diffs(differences.last().unwrap()));
+        }
+        Err(format!(
+            "the differences of {} readings never reach zero",
+            readings.len()
+        ))
+    }
+
+    fn degree(&self) -> isize {
+        self.coefficients.len() as isize - 1
+    }
+
+    // C(x, j) stays an integer for negative x too, so the result is exact. Far from the readings
+    // the value easily outgrows i128, so every step is checked whether or not the `checked`
+    // feature is on, and None means it doesn't fit.
+    fn at(&self, x: i128) -> Option<i128> {
+        let mut binomial: i128 = 1;
+        let mut value: i128 = 0;
+        for (j, coefficient) in self.coefficients.iter().enumerate() {
+            if j > 0 {
+                binomial = next_binomial(binomial, x, j as i128 - 1)?;
             }
-            let next_value: isize = differences.iter().map(|step| step.last().unwrap()).sum();
-            next_value
-        })
-        .sum();
+            value = value.checked_add(coefficient.checked_mul(binomial)?)?;
+        }
+        Some(value)
+    }
+}
+
+// C(x, j + 1) from C(x, j) = `binomial`. The product binomial * (x - j) is divisible by j + 1,
+// so dividing out their common factor first keeps the intermediate within range whenever the
+// result is.
+fn next_binomial(binomial: i128, x: i128, j: i128) -> Option<i128> {
+    let divisor = j + 1;
+    let common = gcd(binomial, divisor);
+    (binomial / common).checked_mul(x.checked_sub(j)? / (divisor / common))
+}
 
-    println!("Part 1: {result}");
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.unsigned_abs(), b.unsigned_abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a as i128
 }
 
-fn part_2() {
-    let data = fs::read_to_string("res/day_9.txt").unwrap();
-    let lines = data.split('\n');
+fn fail(error: &str) -> ! {
+    eprintln!("{error}");
+    process::exit(1);
+}
 
-    let result: isize = lines
-        .map(|line| {
-            let mut reading: Vec<isize> = line
+fn parse_input(data: &str) -> Vec<(Vec<i128>, Polynomial)> {
+    data.split('\n')
+        .enumerate()
+        .map(|(i, line)| {
+            let readings: Vec<i128> = line
                 .split_ascii_whitespace()
                 .map(|reading| str::parse(reading).unwrap())
                 .collect();
-            reading.reverse();
-            let mut differences: Vec<Vec<isize>> = vec![reading];
-            while differences.is_empty() || differences.last().unwrap().iter().any(|d| *d != 0) {
-                differences.push(get_diffs(differences.last().unwrap()));
-            }
-            let next_value: isize = differences.iter().map(|step| step.last().unwrap()).sum();
-            next_value
+            let polynomial = Polynomial::fit(&readings)
+                .unwrap_or_else(|error| panic!("Line {}: {error}", i + 1));
+            (readings, polynomial)
         })
-        .sum();
+        .collect()
+}
 
-    println!("Part 2: {result}");
+// The value K positions past the last reading, or before the first reading when K is negative.
+// Exits if it doesn't fit in i128.
+fn value_at(line: usize, readings: &[i128], polynomial: &Polynomial, offset: i128) -> i128 {
+    let x = if offset >= 0 {
+        (readings.len() as i128 - 1).checked_add(offset)
+    } else {
+        Some(offset)
+    };
+    x.and_then(|x| polynomial.at(x)).unwrap_or_else(|| {
+        fail(&format!(
+            "Line {line}: value exceeds i128 at offset {offset}"
+        ))
+    })
 }
 
 fn main() {
-    part_1();
-    part_2();
+    let data = input::read(9);
+    let sequences = parse_input(&data);
+
+    // Part 1 is the position after the last reading and part 2 the one before the first
+    let part_1: i128 = sequences
+        .iter()
+        .enumerate()
+        .map(|(i, (readings, polynomial))| value_at(i + 1, readings, polynomial, 1))
+        .checked_sum();
+    let part_2: i128 = sequences
+        .iter()
+        .enumerate()
+        .map(|(i, (readings, polynomial))| value_at(i + 1, readings, polynomial, -1))
+        .checked_sum();
+
+    println!("Part 1: {part_1}");
+    println!("Part 2: {part_2}");
+
+    // `--offset K` extrapolates every line to offset K and reports each line's degree
+    if let Some(offset) = args::value("offset") {
+        let offset: i128 = offset.parse().expect("Invalid offset");
+        let mut sum: i128 = 0;
+        println!();
+        for (i, (readings, polynomial)) in sequences.iter().enumerate() {
+            let value = value_at(i + 1, readings, polynomial, offset);
+            sum = sum
+                .checked_add(value)
+                .unwrap_or_else(|| fail(&format!("Sum exceeds i128 at offset {offset}")));
+            println!(
+                "Line {}: degree {}, value at offset {offset}: {value}",
+                i + 1,
+                polynomial.degree()
+            );
+        }
+        println!("Sum at offset {offset}: {sum}");
+    }
 }
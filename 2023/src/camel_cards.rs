@@ -0,0 +1,129 @@
+use crate::{args, checked};
+
+// How a game of Camel Cards is played: the cards from weakest to strongest, which of them (if
+// any) stands in for whatever makes the best hand, and how many cards a hand holds
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rules {
+    pub order: String,
+    pub wild: Option<char>,
+    pub hand_size: usize,
+}
+
+impl Rules {
+    pub fn standard() -> Self {
+        Self {
+            order: String::from("23456789TJQKA"),
+            wild: None,
+            hand_size: 5,
+        }
+    }
+
+    // Jacks become jokers, which are wild but the weakest card when breaking ties
+    pub fn jokers() -> Self {
+        Self {
+            order: String::from("J23456789TQKA"),
+            wild: Some('J'),
+            hand_size: 5,
+        }
+    }
+
+    // Starts from `self` and applies any `--order`, `--wild` and `--hand-size` given
+    pub fn with_args(mut self) -> Self {
+        if let Some(order) = args::value("order") {
+            self.order = order;
+        }
+        if let Some(wild) = args::value("wild") {
+            let mut chars = wild.chars();
+            self.wild = match (chars.next(), chars.next()) {
+                (Some(card), None) => Some(card),
+                _ if wild == "none" => None,
+                _ => panic!("Invalid wild card: {wild}"),
+            };
+        }
+        if let Some(hand_size) = args::value("hand-size") {
+            self.hand_size = hand_size.parse().expect("Invalid hand size");
+        }
+        self
+    }
+
+    fn strength(&self, card: char) -> usize {
+        self.order
+            .chars()
+            .position(|c| c == card)
+            .unwrap_or_else(|| panic!("Invalid card: {card}"))
+    }
+
+    // The hand type is the card counts from largest to smallest, with any wild cards added to the
+    // largest. Comparing these lexicographically ranks the usual types: five of a kind [5] beats
+    // four of a kind [4, 1], which beats a full house [3, 2], and so on down to high card.
+    pub fn hand_type(&self, hand: &str) -> Vec<usize> {
+        let mut counts = vec![0; self.order.chars().count()];
+        let mut wild = 0;
+        for card in hand.chars() {
+            if Some(card) == self.wild {
+                wild += 1;
+            } else {
+                counts[self.strength(card)] += 1;
+            }
+        }
+
+        let mut counts: Vec<usize> = counts.into_iter().filter(|count| *count > 0).collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        match counts.first_mut() {
+            Some(largest) => *largest += wild,
+            None => counts.push(wild),
+        }
+        counts
+    }
+
+    // Packs the hand type followed by each card's strength into one integer, so sorting hands by
+    // key ranks them
+    pub fn key(&self, hand: &str) -> u128 {
+        let cards: Vec<char> = hand.chars().collect();
+        assert_eq!(
+            cards.len(),
+            self.hand_size,
+            "Hand {hand} doesn't have {} cards",
+            self.hand_size
+        );
+
+        let count_bits = usize::BITS - self.hand_size.leading_zeros();
+        let card_bits = usize::BITS - (self.order.chars().count() - 1).leading_zeros();
+        assert!(
+            self.hand_size as u32 * (count_bits + card_bits) <= u128::BITS,
+            "Hands of {} cards don't fit in a sort key",
+            self.hand_size
+        );
+
+        let mut hand_type = self.hand_type(hand);
+        hand_type.resize(self.hand_size, 0);
+
+        let mut key = 0u128;
+        for count in hand_type {
+            key = key << count_bits | count as u128;
+        }
+        for card in cards {
+            key = key << card_bits | self.strength(card) as u128;
+        }
+        key
+    }
+
+    // Ranks every `hand bid` line from weakest to strongest and sums each bid times its rank
+    pub fn winnings(&self, input: &str) -> u64 {
+        let mut hands: Vec<(u128, u64)> = input
+            .split('\n')
+            .map(|line| {
+                let (hand, bid) = line.split_once(' ').unwrap();
+                (self.key(hand), bid.parse().unwrap())
+            })
+            .collect();
+        hands.sort_unstable_by_key(|(key, _)| *key);
+
+        checked::sum(
+            hands
+                .into_iter()
+                .enumerate()
+                .map(|(i, (_, bid))| checked::mul((i + 1) as u64, bid)),
+        )
+    }
+}
@@ -0,0 +1,160 @@
+use std::{fmt::Display, iter::Product, iter::Sum, ops, panic::Location, path::Path};
+
+// Answer arithmetic. With the `checked` feature every operation is checked and an overflow
+// reports the day, source line and operands; otherwise these compile down to the plain operators.
+pub trait Number:
+    Copy
+    + Display
+    + ops::Add<Output = Self>
+    + ops::Sub<Output = Self>
+    + ops::Mul<Output = Self>
+    + Sum
+    + Product
+{
+    const ZERO: Self;
+    const ONE: Self;
+
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn checked_pow(self, exp: u32) -> Option<Self>;
+    fn pow(self, exp: u32) -> Self;
+}
+
+macro_rules! impl_number {
+    ($($t:ty),*) => {
+        $(
+            impl Number for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn checked_pow(self, exp: u32) -> Option<Self> {
+                    <$t>::checked_pow(self, exp)
+                }
+
+                fn pow(self, exp: u32) -> Self {
+                    <$t>::pow(self, exp)
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+#[cold]
+#[track_caller]
+fn overflow(operation: &str) -> ! {
+    let location = Location::caller();
+    let day = Path::new(location.file())
+        .file_stem()
+        .map_or("unknown day".into(), |stem| stem.to_string_lossy());
+    panic!("Overflow in {day}: {operation} at {location}");
+}
+
+#[inline]
+#[track_caller]
+pub fn add<T: Number>(a: T, b: T) -> T {
+    if cfg!(feature = "checked") {
+        match a.checked_add(b) {
+            Some(result) => result,
+            None => overflow(&format!("{a} + {b}")),
+        }
+    } else {
+        a + b
+    }
+}
+
+#[inline]
+#[track_caller]
+pub fn sub<T: Number>(a: T, b: T) -> T {
+    if cfg!(feature = "checked") {
+        match a.checked_sub(b) {
+            Some(result) => result,
+            None => overflow(&format!("{a} - {b}")),
+        }
+    } else {
+        a - b
+    }
+}
+
+#[inline]
+#[track_caller]
+pub fn mul<T: Number>(a: T, b: T) -> T {
+    if cfg!(feature = "checked") {
+        match a.checked_mul(b) {
+            Some(result) => result,
+            None => overflow(&format!("{a} * {b}")),
+        }
+    } else {
+        a * b
+    }
+}
+
+#[inline]
+#[track_caller]
+pub fn pow<T: Number>(base: T, exp: u32) -> T {
+    if cfg!(feature = "checked") {
+        match base.checked_pow(exp) {
+            Some(result) => result,
+            None => overflow(&format!("{base} ^ {exp}")),
+        }
+    } else {
+        base.pow(exp)
+    }
+}
+
+#[inline]
+#[track_caller]
+pub fn sum<T: Number>(values: impl IntoIterator<Item = T>) -> T {
+    if cfg!(feature = "checked") {
+        let mut sum = T::ZERO;
+        for value in values {
+            sum = add(sum, value);
+        }
+        sum
+    } else {
+        values.into_iter().sum()
+    }
+}
+
+#[inline]
+#[track_caller]
+pub fn product<T: Number>(values: impl IntoIterator<Item = T>) -> T {
+    if cfg!(feature = "checked") {
+        let mut product = T::ONE;
+        for value in values {
+            product = mul(product, value);
+        }
+        product
+    } else {
+        values.into_iter().product()
+    }
+}
+
+// Lets iterator chains end in `.checked_sum()` / `.checked_product()` instead of `.sum()` / `.product()`
+pub trait CheckedIterator<T: Number>: Iterator<Item = T> + Sized {
+    #[track_caller]
+    fn checked_sum(self) -> T {
+        sum(self)
+    }
+
+    #[track_caller]
+    fn checked_product(self) -> T {
+        product(self)
+    }
+}
+
+impl<T: Number, I: Iterator<Item = T>> CheckedIterator<T> for I {}
@@ -0,0 +1,54 @@
+use std::{fs, process};
+
+use crate::{aho_corasick::AhoCorasick, args};
+
+const DIGIT_WORDS: [(&str, u32); 9] = [
+    ("one", 1),
+    ("two", 2),
+    ("three", 3),
+    ("four", 4),
+    ("five", 5),
+    ("six", 6),
+    ("seven", 7),
+    ("eight", 8),
+    ("nine", 9),
+];
+
+// Digits always count. Spelled out numbers default to English, or come from `--words <file>`
+// with one `word value` pair per line, e.g. `zero 0`, `first 1` or `eins 1`.
+pub fn matcher() -> AhoCorasick<u32> {
+    let mut patterns: Vec<(String, u32)> =
+        (0..=9).map(|digit| (digit.to_string(), digit)).collect();
+    match args::value("words") {
+        Some(path) => patterns.extend(read_words(&path)),
+        None => patterns.extend(
+            DIGIT_WORDS
+                .iter()
+                .map(|(word, value)| (word.to_string(), *value)),
+        ),
+    }
+    AhoCorasick::new(patterns)
+}
+
+// Exits with the offending line if the file can't be read or a line isn't a `word value` pair
+fn read_words(path: &str) -> Vec<(String, u32)> {
+    let fail = |error: String| -> ! {
+        eprintln!("Invalid words file {path}: {error}");
+        process::exit(1);
+    };
+
+    let words = fs::read_to_string(path).unwrap_or_else(|error| fail(error.to_string()));
+    words
+        .lines()
+        .enumerate()
+        .filter(|(_, line)| !line.trim().is_empty())
+        .map(|(i, line)| {
+            line.trim()
+                .split_once(char::is_whitespace)
+                .and_then(|(word, value)| Some((word.to_string(), value.trim().parse().ok()?)))
+                .unwrap_or_else(|| {
+                    fail(format!("line {} isn't a `word value` pair: {line}", i + 1))
+                })
+        })
+        .collect()
+}
@@ -0,0 +1,1033 @@
+use std::{collections::HashSet, fmt::Write as _, str::FromStr};
+
+use crate::rng::Rng;
+
+// `--size` is either a single number or `WIDTH x HEIGHT` for the grid based days. Days that
+// aren't grids only look at the width.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Size {
+    pub width: usize,
+    pub height: usize,
+}
+
+impl Size {
+    pub const fn square(size: usize) -> Self {
+        Self {
+            width: size,
+            height: size,
+        }
+    }
+}
+
+impl FromStr for Size {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let parse = |value: &str| {
+            value
+                .trim()
+                .parse::<usize>()
+                .ok()
+                .filter(|size| *size > 0)
+                .ok_or_else(|| format!("Invalid size: {value}"))
+        };
+        match value.split_once('x') {
+            Some((width, height)) => Ok(Self {
+                width: parse(width)?,
+                height: parse(height)?,
+            }),
+            None => Ok(Self::square(parse(value)?)),
+        }
+    }
+}
+
+pub struct Generator {
+    pub day: u32,
+    // What `--size` controls for this day
+    pub size: &'static str,
+    pub default_size: Size,
+    pub generate: fn(&mut Rng, Size) -> String,
+}
+
+pub const GENERATORS: &[Generator] = &[
+    Generator {
+        day: 1,
+        size: "number of lines",
+        default_size: Size::square(1000),
+        generate: day_1,
+    },
+    Generator {
+        day: 2,
+        size: "number of games",
+        default_size: Size::square(100),
+        generate: day_2,
+    },
+    Generator {
+        day: 3,
+        size: "schematic dimensions",
+        default_size: Size::square(140),
+        generate: day_3,
+    },
+    Generator {
+        day: 4,
+        size: "number of cards",
+        default_size: Size::square(200),
+        generate: day_4,
+    },
+    Generator {
+        day: 5,
+        size: "number of seed ranges",
+        default_size: Size::square(10),
+        generate: day_5,
+    },
+    Generator {
+        day: 6,
+        size: "number of races, at most 9",
+        default_size: Size::square(4),
+        generate: day_6,
+    },
+    Generator {
+        day: 7,
+        size: "number of hands",
+        default_size: Size::square(1000),
+        generate: day_7,
+    },
+    Generator {
+        day: 8,
+        size: "instruction length",
+        default_size: Size::square(20),
+        generate: day_8,
+    },
+    Generator {
+        day: 9,
+        size: "number of sequences",
+        default_size: Size::square(200),
+        generate: day_9,
+    },
+    Generator {
+        day: 10,
+        size: "grid dimensions",
+        default_size: Size::square(140),
+        generate: day_10,
+    },
+    Generator {
+        day: 11,
+        size: "grid dimensions",
+        default_size: Size::square(140),
+        generate: day_11,
+    },
+    Generator {
+        day: 12,
+        size: "number of rows",
+        default_size: Size::square(1000),
+        generate: day_12,
+    },
+    Generator {
+        day: 13,
+        size: "number of patterns",
+        default_size: Size::square(100),
+        generate: day_13,
+    },
+    Generator {
+        day: 14,
+        size: "platform dimensions",
+        default_size: Size::square(100),
+        generate: day_14,
+    },
+    Generator {
+        day: 15,
+        size: "number of steps",
+        default_size: Size::square(4000),
+        generate: day_15,
+    },
+    Generator {
+        day: 16,
+        size: "grid dimensions",
+        default_size: Size::square(110),
+        generate: day_16,
+    },
+    Generator {
+        day: 18,
+        size: "lagoon outline grid dimensions",
+        default_size: Size::square(30),
+        generate: day_18,
+    },
+];
+
+pub fn generator(day: u32) -> Option<&'static Generator> {
+    GENERATORS.iter().find(|generator| generator.day == day)
+}
+
+const DIGIT_WORDS: [&str; 9] = [
+    "one", "two", "three", "four", "five", "six", "seven", "eight", "nine",
+];
+
+fn lowercase(rng: &mut Rng, length: usize) -> String {
+    (0..length)
+        .map(|_| (b'a' + rng.below(26) as u8) as char)
+        .collect()
+}
+
+fn grid_to_string(grid: &[Vec<char>]) -> String {
+    grid.iter()
+        .map(|row| row.iter().collect::<String>())
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn day_1(rng: &mut Rng, size: Size) -> String {
+    (0..size.width)
+        .map(|_| {
+            let parts = rng.range(1..=8);
+            // Every line needs at least one real digit for part 1
+            let digit_part = rng.below(parts as u64) as usize;
+            let mut line = String::new();
+            for part in 0..parts {
+                match rng.below(3) {
+                    0 => {
+                        let length = rng.range(1..=4);
+                        line.push_str(&lowercase(rng, length));
+                    }
+                    1 => {
+                        let word = rng.choose(&DIGIT_WORDS);
+                        line.push_str(word);
+                    }
+                    _ => line.push((b'1' + rng.below(9) as u8) as char),
+                }
+                if part == digit_part {
+                    line.push((b'1' + rng.below(9) as u8) as char);
+                }
+            }
+            line
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn day_2(rng: &mut Rng, size: Size) -> String {
+    const COLOURS: [&str; 3] = ["red", "green", "blue"];
+
+    (1..=size.width)
+        .map(|id| {
+            let mut pulls: Vec<Vec<(usize, &str)>> = (0..rng.range(1..=6))
+                .map(|_| {
+                    let mut colours = COLOURS.to_vec();
+                    rng.shuffle(&mut colours);
+                    colours.truncate(rng.range(1..=3));
+                    colours
+                        .into_iter()
+                        .map(|colour| (rng.range(1..=20), colour))
+                        .collect()
+                })
+                .collect();
+
+            // Every colour shows up at least once per game
+            for colour in COLOURS {
+                if !pulls.iter().flatten().any(|(_, c)| *c == colour) {
+                    let pull = rng.below(pulls.len() as u64) as usize;
+                    pulls[pull].push((rng.range(1..=20), colour));
+                }
+            }
+
+            let pulls: Vec<String> = pulls
+                .iter()
+                .map(|pull| {
+                    pull.iter()
+                        .map(|(count, colour)| format!("{count} {colour}"))
+                        .collect::<Vec<String>>()
+                        .join(", ")
+                })
+                .collect();
+            format!("Game {id}: {}", pulls.join("; "))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn day_3(rng: &mut Rng, size: Size) -> String {
+    const SYMBOLS: [char; 10] = ['*', '#', '+', '$', '/', '@', '%', '=', '&', '-'];
+
+    let grid: Vec<Vec<char>> = (0..size.height)
+        .map(|_| {
+            let mut row = Vec::with_capacity(size.width);
+            while row.len() < size.width {
+                let remaining = size.width - row.len();
+                if rng.chance(0.12) {
+                    let number = rng.range(1..=999).to_string();
+                    if number.len() <= remaining {
+                        row.extend(number.chars());
+                        // Keep numbers apart so they don't merge into one
+                        if row.len() < size.width {
+                            row.push('.');
+                        }
+                        continue;
+                    }
+                }
+                if rng.chance(0.06) {
+                    row.push(*rng.choose(&SYMBOLS));
+                } else {
+                    row.push('.');
+                }
+            }
+            row
+        })
+        .collect();
+
+    grid_to_string(&grid)
+}
+
+fn day_4(rng: &mut Rng, size: Size) -> String {
+    const WINNING: usize = 10;
+    const NUMBERS: usize = 25;
+
+    let cards = size.width;
+    let id_width = cards.to_string().len();
+    let format_numbers = |numbers: &[usize]| {
+        numbers
+            .iter()
+            .map(|number| format!("{number:>2}"))
+            .collect::<Vec<String>>()
+            .join(" ")
+    };
+
+    (0..cards)
+        .map(|i| {
+            let mut pool: Vec<usize> = (1..=99).collect();
+            rng.shuffle(&mut pool);
+            let winning = &pool[..WINNING];
+            let others = &pool[WINNING..];
+
+            // Most cards lose, like the real input, and none win copies of cards past the end
+            // of the table
+            let matches = if rng.chance(0.5) {
+                0
+            } else {
+                rng.range(0..=WINNING.min(cards - 1 - i))
+            };
+            let mut numbers: Vec<usize> = winning[..matches].to_vec();
+            numbers.extend_from_slice(&others[..NUMBERS - matches]);
+            rng.shuffle(&mut numbers);
+
+            format!(
+                "Card {:>id_width$}: {} | {}",
+                i + 1,
+                format_numbers(winning),
+                format_numbers(&numbers)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+fn day_5(rng: &mut Rng, size: Size) -> String {
+    const MAPS: [&str; 7] = [
+        "seed-to-soil",
+        "soil-to-fertilizer",
+        "fertilizer-to-water",
+        "water-to-light",
+        "light-to-temperature",
+        "temperature-to-humidity",
+        "humidity-to-location",
+    ];
+
+    let universe = (100 * size.width).max(1000);
+
+    let mut output = String::from("seeds:");
+    for _ in 0..size.width {
+        let start = rng.range(0..=universe - 1);
+        let length = rng.range(1..=universe / 10);
+        write!(output, " {start} {length}").unwrap();
+    }
+
+    for name in MAPS {
+        write!(output, "\n\n{name} map:").unwrap();
+
+        // Cut the universe into segments and send each one to a shuffled position, so every
+        // map is a bijection like the real ones
+        let mut cuts: Vec<usize> = (0..rng.range(1..=5))
+            .map(|_| rng.range(1..=universe - 1))
+            .collect();
+        cuts.push(0);
+        cuts.push(universe);
+        cuts.sort_unstable();
+        cuts.dedup();
+
+        let segments: Vec<(usize, usize)> = cuts.windows(2).map(|w| (w[0], w[1] - w[0])).collect();
+        let mut order: Vec<usize> = (0..segments.len()).collect();
+        rng.shuffle(&mut order);
+
+        let mut destination = 0;
+        let mut mappings = Vec::with_capacity(segments.len());
+        for i in order {
+            let (source, length) = segments[i];
+            mappings.push((destination, source, length));
+            destination += length;
+        }
+        rng.shuffle(&mut mappings);
+
+        // Sometimes leave a gap that maps to itself, or add a mapping overlapping the others, so
+        // solvers have to get uncovered values and the first matching mapping right
+        if mappings.len() > 1 && rng.chance(0.3) {
+            let gap = rng.below(mappings.len() as u64) as usize;
+            mappings.remove(gap);
+        }
+        if rng.chance(0.3) {
+            let source = rng.range(0..=universe - 1);
+            let length = rng.range(1..=universe - source);
+            let destination = rng.range(0..=universe - length);
+            let position = rng.range(0..=mappings.len());
+            mappings.insert(position, (destination, source, length));
+        }
+
+        for (destination, source, length) in mappings {
+            write!(output, "\n{destination} {source} {length}").unwrap();
+        }
+    }
+
+    output
+}
+
+// Part 2 joins every distance into one number of up to four digits per race, which has to fit in
+// the solver's 128 bits (38 full digits)
+const DAY_6_MAX_RACES: usize = 9;
+
+fn day_6(rng: &mut Rng, size: Size) -> String {
+    let races: Vec<(usize, usize)> = (0..size.width.min(DAY_6_MAX_RACES))
+        .map(|_| {
+            let time = rng.range(30..=99);
+            let record = (time / 2) * (time - time / 2);
+            // At least one way to win, and three digits per distance like the real input. Half
+            // the time the distance is one a hold time reaches exactly, which isn't a win.
+            if rng.chance(0.5) {
+                let hold = rng.range(1..=time / 2 - 1);
+                let distance = hold * (time - hold);
+                if distance >= 100 {
+                    return (time, distance);
+                }
+            }
+            (time, rng.range(100..=record - 1))
+        })
+        .collect();
+
+    let mut times = String::from("Time:    ");
+    let mut distances = String::from("Distance:");
+    for (time, distance) in races {
+        write!(times, " {time:>4}").unwrap();
+        write!(distances, " {distance:>4}").unwrap();
+    }
+    format!("{times}\n{distances}")
+}
+
+fn day_7(rng: &mut Rng, size: Size) -> String {
+    const CARDS: [char; 13] = [
+        '2', '3', '4', '5', '6', '7', '8', '9', 'T', 'J', 'Q', 'K', 'A',
+    ];
+
+    // Hands are all different, so there's only one way to rank them
+    let mut seen = HashSet::new();
+    let mut hands = Vec::with_capacity(size.width);
+    while hands.len() < size.width {
+        let hand: String = (0..5).map(|_| *rng.choose(&CARDS)).collect();
+        if seen.insert(hand.clone()) {
+            hands.push(format!("{hand} {}", rng.range(1..=1000)));
+        }
+    }
+    hands.join("\n")
+}
+
+fn day_8(rng: &mut Rng, size: Size) -> String {
+    const GHOSTS: usize = 6;
+    const PRIMES: [usize; 10] = [2, 3, 5, 7, 11, 13, 17, 19, 23, 29];
+
+    let instruction_count = size.width;
+    let instructions: String = (0..instruction_count)
+        .map(|_| if rng.chance(0.5) { 'L' } else { 'R' })
+        .collect();
+
+    let mut primes = PRIMES.to_vec();
+    rng.shuffle(&mut primes);
+    primes.truncate(GHOSTS);
+
+    // Names are three letters like the real input, unless there are too many nodes for that.
+    // Leave plenty of spare names so picking unused ones at random stays quick.
+    let inner_nodes: usize = primes.iter().map(|p| p * instruction_count).sum();
+    let mut length = 3;
+    while 26usize.pow(length as u32 - 1) * 24 < 2 * inner_nodes {
+        length += 1;
+    }
+
+    let mut used = HashSet::from(["AAA".to_string(), "ZZZ".to_string()]);
+    let mut name = |rng: &mut Rng, last: Option<u8>| loop {
+        let letter = |rng: &mut Rng| b'A' + rng.below(26) as u8;
+        let last = last.unwrap_or_else(|| loop {
+            let letter = letter(rng);
+            if letter != b'A' && letter != b'Z' {
+                break letter;
+            }
+        });
+        let mut name: Vec<u8> = (1..length).map(|_| letter(rng)).collect();
+        name.push(last);
+        let name = String::from_utf8(name).unwrap();
+        if used.insert(name.clone()) {
+            break name;
+        }
+    };
+
+    // Each ghost walks a chain of nodes, one per step, ending on its Z node after `prime`
+    // passes through the instructions and then looping back to the start of the chain. Every
+    // node is only ever visited at one instruction index, so the edge not taken can point anywhere.
+    let mut nodes: Vec<(String, [String; 2])> = Vec::new();
+    for (ghost, prime) in primes.into_iter().enumerate() {
+        let length = prime * instruction_count;
+        let mut chain = Vec::with_capacity(length + 1);
+        chain.push(if ghost == 0 {
+            "AAA".to_string()
+        } else {
+            name(rng, Some(b'A'))
+        });
+        for _ in 1..length {
+            chain.push(name(rng, None));
+        }
+        chain.push(if ghost == 0 {
+            "ZZZ".to_string()
+        } else {
+            name(rng, Some(b'Z'))
+        });
+
+        for (step, node) in chain.iter().enumerate() {
+            let next = if step == length { 1 } else { step + 1 };
+            let taken = (instructions.as_bytes()[step % instruction_count] == b'R') as usize;
+            let mut directions = [
+                chain[rng.range(1..=length)].clone(),
+                chain[rng.range(1..=length)].clone(),
+            ];
+            directions[taken] = chain[next].clone();
+            nodes.push((node.clone(), directions));
+        }
+    }
+    rng.shuffle(&mut nodes);
+
+    let mut output = format!("{instructions}\n");
+    for (node, [left, right]) in nodes {
+        write!(output, "\n{node} = ({left}, {right})").unwrap();
+    }
+    output
+}
+
+fn day_9(rng: &mut Rng, size: Size) -> String {
+    const VALUES: usize = 21;
+
+    (0..size.width)
+        .map(|_| {
+            // Newton form with integer coefficients always gives integer values
+            let degree = rng.range(0..=8);
+            let coefficients: Vec<i64> = (0..=degree)
+                .map(|j| loop {
+                    let coefficient = rng.range(0..=20) as i64 - 10;
+                    if coefficient != 0 || j != degree {
+                        break coefficient;
+                    }
+                })
+                .collect();
+
+            (0..VALUES as i64)
+                .map(|x| {
+                    let mut binomial = 1i64;
+                    let mut value = 0i64;
+                    for (j, coefficient) in coefficients.iter().enumerate() {
+                        value += coefficient * binomial;
+                        binomial = binomial * (x - j as i64) / (j as i64 + 1);
+                    }
+                    value.to_string()
+                })
+                .collect::<Vec<String>>()
+                .join(" ")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// A random hole-free polyomino on a `width` × `height` grid of cells in which no two cells
+// touch only at a corner, so that its outline is a single simple loop through the grid's vertices
+fn polyomino(rng: &mut Rng, width: usize, height: usize, coverage: f64) -> Vec<Vec<bool>> {
+    let mut cells = vec![vec![false; width]; height];
+    let start = (
+        rng.below(width as u64) as usize,
+        rng.below(height as u64) as usize,
+    );
+    cells[start.1][start.0] = true;
+    let mut members = vec![start];
+
+    let target = ((width * height) as f64 * coverage).max(1.0) as usize;
+    while members.len() < target {
+        let (x, y) = *rng.choose(&members);
+        let (x, y) = match rng.below(4) {
+            0 if y > 0 => (x, y - 1),
+            1 if y + 1 < height => (x, y + 1),
+            2 if x > 0 => (x - 1, y),
+            3 if x + 1 < width => (x + 1, y),
+            _ => continue,
+        };
+        if !cells[y][x] {
+            cells[y][x] = true;
+            members.push((x, y));
+        }
+    }
+
+    let cell = |cells: &Vec<Vec<bool>>, x: isize, y: isize| {
+        x >= 0
+            && y >= 0
+            && (x as usize) < width
+            && (y as usize) < height
+            && cells[y as usize][x as usize]
+    };
+
+    loop {
+        // Fill holes: anything not reachable from outside the shape becomes part of it
+        let mut outside = vec![vec![false; width]; height];
+        let mut queue: Vec<(usize, usize)> = Vec::new();
+        for y in 0..height {
+            for x in 0..width {
+                if (x == 0 || y == 0 || x + 1 == width || y + 1 == height) && !cells[y][x] {
+                    outside[y][x] = true;
+                    queue.push((x, y));
+                }
+            }
+        }
+        while let Some((x, y)) = queue.pop() {
+            let neighbours = [
+                (x.wrapping_sub(1), y),
+                (x + 1, y),
+                (x, y.wrapping_sub(1)),
+                (x, y + 1),
+            ];
+            for (nx, ny) in neighbours {
+                if nx < width && ny < height && !cells[ny][nx] && !outside[ny][nx] {
+                    outside[ny][nx] = true;
+                    queue.push((nx, ny));
+                }
+            }
+        }
+        for y in 0..height {
+            for x in 0..width {
+                cells[y][x] = cells[y][x] || !outside[y][x];
+            }
+        }
+
+        // Resolve pinches, where two cells or two gaps only meet diagonally
+        let mut pinched = false;
+        for y in 0..=height as isize {
+            for x in 0..=width as isize {
+                let top_left = cell(&cells, x - 1, y - 1);
+                let top_right = cell(&cells, x, y - 1);
+                let bottom_left = cell(&cells, x - 1, y);
+                let bottom_right = cell(&cells, x, y);
+                if top_left == bottom_right && top_right == bottom_left && top_left != top_right {
+                    let (gap_x, gap_y) = match (top_left, rng.chance(0.5)) {
+                        (true, true) => (x, y - 1),
+                        (true, false) => (x - 1, y),
+                        (false, true) => (x - 1, y - 1),
+                        (false, false) => (x, y),
+                    };
+                    cells[gap_y as usize][gap_x as usize] = true;
+                    pinched = true;
+                }
+            }
+        }
+
+        if !pinched {
+            return cells;
+        }
+    }
+}
+
+// Walks the outline of a polyomino, returning the grid vertices it passes through in order,
+// starting from its top left corner
+fn outline(cells: &[Vec<bool>]) -> Vec<(usize, usize)> {
+    let height = cells.len() as isize;
+    let width = cells[0].len() as isize;
+    let cell = |x: isize, y: isize| {
+        x >= 0 && y >= 0 && x < width && y < height && cells[y as usize][x as usize]
+    };
+    // Whether the edge to the east / south of a vertex lies on the outline
+    let east = |x: isize, y: isize| x >= 0 && x < width && cell(x, y - 1) != cell(x, y);
+    let south = |x: isize, y: isize| y >= 0 && y < height && cell(x - 1, y) != cell(x, y);
+
+    let start = (0..=height)
+        .flat_map(|y| (0..=width).map(move |x| (x, y)))
+        .find(|&(x, y)| east(x, y))
+        .unwrap();
+
+    let mut vertices = vec![start];
+    let mut previous = start;
+    let mut current = start;
+    loop {
+        let (x, y) = current;
+        let candidates = [
+            (east(x, y), (x + 1, y)),
+            (east(x - 1, y), (x - 1, y)),
+            (south(x, y), (x, y + 1)),
+            (south(x, y - 1), (x, y - 1)),
+        ];
+        let next = candidates
+            .into_iter()
+            .find(|&(edge, next)| edge && next != previous)
+            .map(|(_, next)| next)
+            .unwrap();
+        if next == start {
+            break;
+        }
+        vertices.push(next);
+        previous = current;
+        current = next;
+    }
+
+    vertices
+        .into_iter()
+        .map(|(x, y)| (x as usize, y as usize))
+        .collect()
+}
+
+fn day_10(rng: &mut Rng, size: Size) -> String {
+    const JUNK: [char; 7] = ['|', '-', 'L', 'J', '7', 'F', '.'];
+
+    // The loop runs along the vertices of a polyomino one cell smaller than the tile grid
+    let width = size.width.max(3);
+    let height = size.height.max(3);
+    let cells = polyomino(rng, width - 1, height - 1, 0.4);
+    let vertices = outline(&cells);
+
+    let mut grid: Vec<Vec<char>> = (0..height)
+        .map(|_| (0..width).map(|_| *rng.choose(&JUNK)).collect())
+        .collect();
+
+    let mut straights = Vec::new();
+    for (i, &(x, y)) in vertices.iter().enumerate() {
+        let previous = vertices[(i + vertices.len() - 1) % vertices.len()];
+        let next = vertices[(i + 1) % vertices.len()];
+        let connects = |direction: (isize, isize)| {
+            [previous, next]
+                .iter()
+                .any(|&(nx, ny)| (nx as isize - x as isize, ny as isize - y as isize) == direction)
+        };
+        let (north, south, east, west) = (
+            connects((0, -1)),
+            connects((0, 1)),
+            connects((1, 0)),
+            connects((-1, 0)),
+        );
+        grid[y][x] = match (north, south, east, west) {
+            (true, true, _, _) => '|',
+            (_, _, true, true) => {
+                straights.push((x, y));
+                '-'
+            }
+            (true, _, true, _) => 'L',
+            (true, _, _, true) => 'J',
+            (_, true, _, true) => '7',
+            _ => 'F',
+        };
+    }
+
+    // Prefer starting on a horizontal straight, otherwise anywhere on the loop
+    let (start_x, start_y) = if straights.is_empty() {
+        *rng.choose(&vertices)
+    } else {
+        *rng.choose(&straights)
+    };
+    grid[start_y][start_x] = 'S';
+
+    // Junk next to the start must not look like it connects to it
+    let on_loop: HashSet<(usize, usize)> = vertices.iter().copied().collect();
+    let neighbours = [
+        (start_x, start_y.wrapping_sub(1), ['|', '7', 'F']),
+        (start_x, start_y + 1, ['|', 'L', 'J']),
+        (start_x.wrapping_sub(1), start_y, ['-', 'L', 'F']),
+        (start_x + 1, start_y, ['-', 'J', '7']),
+    ];
+    for (x, y, pointing) in neighbours {
+        if x < width && y < height && !on_loop.contains(&(x, y)) && pointing.contains(&grid[y][x]) {
+            grid[y][x] = '.';
+        }
+    }
+
+    grid_to_string(&grid)
+}
+
+fn day_11(rng: &mut Rng, size: Size) -> String {
+    // A pair of galaxies needs at least two cells, so a 1x1 grid is widened to 2x1
+    let size = if size.width * size.height < 2 {
+        Size {
+            width: 2,
+            height: 1,
+        }
+    } else {
+        size
+    };
+    let mut grid = vec![vec!['.'; size.width]; size.height];
+    let empty_rows: HashSet<usize> = (0..size.height).filter(|_| rng.chance(0.1)).collect();
+    let empty_columns: HashSet<usize> = (0..size.width).filter(|_| rng.chance(0.1)).collect();
+
+    let mut galaxies = 0;
+    for (y, row) in grid.iter_mut().enumerate() {
+        for (x, cell) in row.iter_mut().enumerate() {
+            if !empty_rows.contains(&y) && !empty_columns.contains(&x) && rng.chance(0.02) {
+                *cell = '#';
+                galaxies += 1;
+            }
+        }
+    }
+
+    // There have to be at least two galaxies to make a pair
+    while galaxies < 2 {
+        let x = rng.below(size.width as u64) as usize;
+        let y = rng.below(size.height as u64) as usize;
+        if grid[y][x] != '#' {
+            grid[y][x] = '#';
+            galaxies += 1;
+        }
+    }
+
+    grid_to_string(&grid)
+}
+
+fn day_12(rng: &mut Rng, size: Size) -> String {
+    (0..size.width)
+        .map(|_| {
+            // Start from a concrete arrangement so every row has at least one solution
+            let length = rng.range(5..=20);
+            let mut springs: Vec<bool> = (0..length).map(|_| rng.chance(0.45)).collect();
+            if !springs.contains(&true) {
+                springs[rng.below(length as u64) as usize] = true;
+            }
+
+            let groups: Vec<String> = springs
+                .split(|damaged| !damaged)
+                .filter(|group| !group.is_empty())
+                .map(|group| group.len().to_string())
+                .collect();
+
+            let conditions: String = springs
+                .iter()
+                .map(|&damaged| match (rng.chance(0.45), damaged) {
+                    (true, _) => '?',
+                    (false, true) => '#',
+                    (false, false) => '.',
+                })
+                .collect();
+
+            format!("{conditions} {}", groups.join(","))
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+// Mismatched cell pairs across the line between rows `line - 1` and `line`
+fn reflection_errors(pattern: &[Vec<bool>], line: usize) -> usize {
+    let span = line.min(pattern.len() - line);
+    (0..span)
+        .map(|offset| {
+            pattern[line - 1 - offset]
+                .iter()
+                .zip(&pattern[line + offset])
+                .filter(|(a, b)| a != b)
+                .count()
+        })
+        .sum()
+}
+
+fn transpose(pattern: &[Vec<bool>]) -> Vec<Vec<bool>> {
+    (0..pattern[0].len())
+        .map(|x| pattern.iter().map(|row| row[x]).collect())
+        .collect()
+}
+
+fn day_13_pattern(rng: &mut Rng) -> Vec<Vec<bool>> {
+    loop {
+        let height = rng.range(5..=15);
+        let width = rng.range(5..=15);
+        let row_line = rng.range(1..=height - 1);
+        let column_line = rng.range(1..=width - 1);
+        let mirror = |i: usize, line: usize, length: usize| {
+            (2 * line).checked_sub(i + 1).filter(|&m| m < length)
+        };
+
+        // Make the pattern symmetric across both lines by giving every orbit one random value
+        let mut cells: Vec<Vec<Option<bool>>> = vec![vec![None; width]; height];
+        for y in 0..height {
+            for x in 0..width {
+                if cells[y][x].is_some() {
+                    continue;
+                }
+                let value = rng.chance(0.5);
+                for ry in [Some(y), mirror(y, row_line, height)].into_iter().flatten() {
+                    for rx in [Some(x), mirror(x, column_line, width)]
+                        .into_iter()
+                        .flatten()
+                    {
+                        cells[ry][rx] = Some(value);
+                    }
+                }
+            }
+        }
+        let mut pattern: Vec<Vec<bool>> = cells
+            .into_iter()
+            .map(|row| row.into_iter().map(Option::unwrap).collect())
+            .collect();
+
+        // Smudge one cell that only has a mirror across the column line, so the row line stays
+        // the part 1 answer and the column line becomes the part 2 answer
+        let smudges: Vec<(usize, usize)> = (0..height)
+            .filter(|&y| mirror(y, row_line, height).is_none())
+            .flat_map(|y| {
+                (0..width)
+                    .filter(move |&x| mirror(x, column_line, width).is_some())
+                    .map(move |x| (x, y))
+            })
+            .collect();
+        if smudges.is_empty() {
+            continue;
+        }
+        let (x, y) = *rng.choose(&smudges);
+        pattern[y][x] = !pattern[y][x];
+
+        // Make sure no other line accidentally reflects, with or without a smudge
+        let columns = transpose(&pattern);
+        let errors: Vec<usize> = (1..height)
+            .map(|line| reflection_errors(&pattern, line))
+            .chain((1..width).map(|line| reflection_errors(&columns, line)))
+            .collect();
+        if errors.iter().filter(|e| **e == 0).count() != 1
+            || errors.iter().filter(|e| **e == 1).count() != 1
+        {
+            continue;
+        }
+
+        return if rng.chance(0.5) { columns } else { pattern };
+    }
+}
+
+fn day_13(rng: &mut Rng, size: Size) -> String {
+    (0..size.width)
+        .map(|_| {
+            day_13_pattern(rng)
+                .iter()
+                .map(|row| {
+                    row.iter()
+                        .map(|&rock| if rock { '#' } else { '.' })
+                        .collect::<String>()
+                })
+                .collect::<Vec<String>>()
+                .join("\n")
+        })
+        .collect::<Vec<String>>()
+        .join("\n\n")
+}
+
+fn day_14(rng: &mut Rng, size: Size) -> String {
+    let grid: Vec<Vec<char>> = (0..size.height)
+        .map(|_| {
+            (0..size.width)
+                .map(|_| match rng.below(100) {
+                    0..=19 => 'O',
+                    20..=34 => '#',
+                    _ => '.',
+                })
+                .collect()
+        })
+        .collect();
+
+    grid_to_string(&grid)
+}
+
+fn day_15(rng: &mut Rng, size: Size) -> String {
+    let labels: Vec<String> = (0..size.width / 4 + 1)
+        .map(|_| {
+            let length = rng.range(2..=6);
+            lowercase(rng, length)
+        })
+        .collect();
+
+    (0..size.width)
+        .map(|_| {
+            let label = rng.choose(&labels);
+            if rng.chance(0.3) {
+                format!("{label}-")
+            } else {
+                format!("{label}={}", rng.range(1..=9))
+            }
+        })
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+fn day_16(rng: &mut Rng, size: Size) -> String {
+    const DEVICES: [char; 4] = ['/', '\\', '|', '-'];
+
+    let grid: Vec<Vec<char>> = (0..size.height)
+        .map(|_| {
+            (0..size.width)
+                .map(|_| {
+                    if rng.chance(0.1) {
+                        *rng.choose(&DEVICES)
+                    } else {
+                        '.'
+                    }
+                })
+                .collect()
+        })
+        .collect();
+
+    grid_to_string(&grid)
+}
+
+fn day_18(rng: &mut Rng, size: Size) -> String {
+    // Largest distance that fits in the five hex digits of part 2
+    const MAX_HEX_DISTANCE: usize = 0xFFFFF;
+
+    let cells = polyomino(rng, size.width, size.height, 0.5);
+    let vertices = outline(&cells);
+
+    // Stretching columns and rows by random amounts keeps the outline simple while giving
+    // each part its own edge lengths
+    let max_stretch = (MAX_HEX_DISTANCE / size.width.max(size.height)).max(1);
+    let mut stretch = |count: usize, max: usize| -> Vec<usize> {
+        (0..count).map(|_| rng.range(1..=max)).collect()
+    };
+    let columns = [stretch(size.width, 10), stretch(size.width, max_stretch)];
+    let rows = [stretch(size.height, 10), stretch(size.height, max_stretch)];
+
+    // Merge consecutive unit edges heading the same way into one instruction
+    let mut instructions: Vec<(char, [usize; 2])> = Vec::new();
+    for (i, &(x, y)) in vertices.iter().enumerate() {
+        let (next_x, next_y) = vertices[(i + 1) % vertices.len()];
+        let (direction, lengths) =
+            match (next_x as isize - x as isize, next_y as isize - y as isize) {
+                (1, 0) => ('R', [columns[0][x], columns[1][x]]),
+                (-1, 0) => ('L', [columns[0][next_x], columns[1][next_x]]),
+                (0, 1) => ('D', [rows[0][y], rows[1][y]]),
+                _ => ('U', [rows[0][next_y], rows[1][next_y]]),
+            };
+        match instructions.last_mut() {
+            Some((last, distances)) if *last == direction => {
+                distances[0] += lengths[0];
+                distances[1] += lengths[1];
+            }
+            _ => instructions.push((direction, lengths)),
+        }
+    }
+
+    instructions
+        .into_iter()
+        .map(|(direction, [distance, hex_distance])| {
+            let hex_direction = match direction {
+                'R' => 0,
+                'D' => 1,
+                'L' => 2,
+                _ => 3,
+            };
+            format!("{direction} {distance} (#{hex_distance:05x}{hex_direction})")
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
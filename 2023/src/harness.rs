@@ -0,0 +1,458 @@
+use std::{
+    collections::BTreeMap,
+    env, fs,
+    path::{Path, PathBuf},
+    process::{self, Command},
+    time::{Duration, Instant},
+};
+
+use crate::{
+    generate::{self, Size},
+    rng::Rng,
+};
+
+// One way of solving a day: a solver binary, the extra arguments that select the implementation
+// and the parts it prints answers for
+pub struct Implementation {
+    pub bin: &'static str,
+    pub args: &'static [&'static str],
+    pub parts: &'static [u32],
+    // Too slow for the real puzzle input, so only run on the example and generated inputs
+    pub exhaustive: bool,
+}
+
+impl Implementation {
+    pub fn name(&self) -> String {
+        let mut name = self.bin.to_string();
+        for arg in self.args {
+            name.push(' ');
+            name.push_str(arg);
+        }
+        name
+    }
+}
+
+pub struct Day {
+    pub day: u32,
+    pub implementations: &'static [Implementation],
+}
+
+pub const DAYS: &[Day] = &[
+    Day {
+        day: 1,
+        implementations: &[
+            Implementation {
+                bin: "day_1",
+                args: &[],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+            Implementation {
+                bin: "day_1_alt",
+                args: &[],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+        ],
+    },
+    Day {
+        day: 2,
+        implementations: &[
+            Implementation {
+                bin: "day_2",
+                args: &[],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+            Implementation {
+                bin: "day_2_alt",
+                args: &[],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+        ],
+    },
+    Day {
+        day: 5,
+        implementations: &[
+            Implementation {
+                bin: "day_5",
+                args: &[],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+            Implementation {
+                bin: "day_5",
+                args: &["--brute-force"],
+                parts: &[1, 2],
+                exhaustive: true,
+            },
+        ],
+    },
+    Day {
+        day: 6,
+        implementations: &[
+            Implementation {
+                bin: "day_6",
+                args: &[],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+            Implementation {
+                bin: "day_6",
+                args: &["--brute-force"],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+        ],
+    },
+    Day {
+        day: 10,
+        implementations: &[
+            Implementation {
+                bin: "day_10",
+                args: &[],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+            Implementation {
+                bin: "day_10",
+                args: &["--area", "shoelace"],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+            Implementation {
+                bin: "day_10",
+                args: &["--area", "flood-fill"],
+                parts: &[1, 2],
+                exhaustive: false,
+            },
+        ],
+    },
+];
+
+pub fn day(day: u32) -> Option<&'static Day> {
+    DAYS.iter().find(|entry| entry.day == day)
+}
+
+pub struct Options {
+    // How many generated inputs to check on top of the real and example inputs
+    pub random: u64,
+    pub size: Option<Size>,
+    // How many times each implementation is timed for the benchmark
+    pub runs: u32,
+}
+
+type Answers = BTreeMap<u32, String>;
+
+// What an implementation printed, or why it failed
+type Outcome = Result<Answers, String>;
+
+struct Input {
+    name: String,
+    text: String,
+    // The real puzzle input, which exhaustive implementations skip
+    real: bool,
+}
+
+// The solver binaries are built next to this one
+fn binary(bin: &str) -> PathBuf {
+    let current = env::current_exe().expect("Failed to locate the current executable");
+    current.with_file_name(format!("{bin}{}", env::consts::EXE_SUFFIX))
+}
+
+fn run(implementation: &Implementation, input: &Path) -> (Outcome, Duration) {
+    let start = Instant::now();
+    let output = Command::new(binary(implementation.bin))
+        .args(implementation.args)
+        .arg("--input")
+        .arg(input)
+        .output();
+    let elapsed = start.elapsed();
+
+    let output = match output {
+        Ok(output) => output,
+        Err(error) => return (Err(format!("failed to start: {error}")), elapsed),
+    };
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        // Skip the `thread 'main' panicked at` header and the backtrace note
+        let message = stderr
+            .lines()
+            .map(str::trim)
+            .find(|line| {
+                !line.is_empty() && !line.starts_with("thread ") && !line.starts_with("note:")
+            })
+            .unwrap_or("no output")
+            .to_string();
+        return (Err(format!("{}: {message}", output.status)), elapsed);
+    }
+
+    let answers = String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let (label, answer) = line.split_once(": ")?;
+            let part = label.strip_prefix("Part ")?.parse().ok()?;
+            Some((part, answer.to_string()))
+        })
+        .collect();
+    (Ok(answers), elapsed)
+}
+
+// The answer given for `part`, or the failure if the implementation didn't finish
+fn answer(outcome: &Outcome, part: u32) -> Result<&str, &str> {
+    match outcome {
+        Ok(answers) => answers.get(&part).map(String::as_str).ok_or("no answer"),
+        Err(error) => Err(error),
+    }
+}
+
+// The implementations that ran and answer `part`, alongside their outcomes
+fn covering<'a>(
+    day: &'a Day,
+    outcomes: &'a [Option<Outcome>],
+    part: u32,
+) -> impl Iterator<Item = (&'a Implementation, &'a Outcome)> {
+    day.implementations
+        .iter()
+        .zip(outcomes)
+        .filter(move |(implementation, _)| implementation.parts.contains(&part))
+        .filter_map(|(implementation, outcome)| Some((implementation, outcome.as_ref()?)))
+}
+
+fn parts(day: &Day) -> Vec<u32> {
+    let mut parts: Vec<u32> = day
+        .implementations
+        .iter()
+        .flat_map(|implementation| implementation.parts.iter().copied())
+        .collect();
+    parts.sort_unstable();
+    parts.dedup();
+    parts
+}
+
+// Parts answered by more than one implementation where the answers don't all match. Failures
+// only count as equal when every implementation fails, so a crash next to an answer is reported.
+fn disagreements(day: &Day, outcomes: &[Option<Outcome>]) -> Vec<u32> {
+    parts(day)
+        .into_iter()
+        .filter(|part| {
+            let answers: Vec<Option<&str>> = covering(day, outcomes, *part)
+                .map(|(_, outcome)| answer(outcome, *part).ok())
+                .collect();
+            answers.len() > 1 && answers.iter().any(|answer| *answer != answers[0])
+        })
+        .collect()
+}
+
+// A file in the temp directory that each trial input is written to before the solvers read it
+struct Scratch {
+    path: PathBuf,
+}
+
+impl Scratch {
+    fn new() -> Self {
+        let path = env::temp_dir().join(format!("aoc-diff-{}.txt", process::id()));
+        Self { path }
+    }
+
+    fn write(&self, input: &str) -> &Path {
+        fs::write(&self.path, input).expect("Failed to write scratch input");
+        &self.path
+    }
+}
+
+impl Drop for Scratch {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+// Shrinks a failing input by deleting runs of lines while `fails` still holds, halving the run
+// length whenever nothing more can be removed (delta debugging over lines)
+fn minimize(input: &str, fails: impl Fn(&str) -> bool) -> String {
+    let mut lines: Vec<&str> = input.split('\n').collect();
+    let mut chunk = lines.len().div_ceil(2);
+    while chunk > 0 {
+        let mut removed = false;
+        let mut start = 0;
+        while start < lines.len() {
+            let end = (start + chunk).min(lines.len());
+            let candidate: Vec<&str> = [&lines[..start], &lines[end..]].concat();
+            if !candidate.is_empty() && fails(&candidate.join("\n")) {
+                lines = candidate;
+                removed = true;
+            } else {
+                start = end;
+            }
+        }
+        if !removed {
+            chunk /= 2;
+        }
+    }
+    lines.join("\n")
+}
+
+fn inputs(day: &Day, options: &Options) -> Vec<Input> {
+    let mut inputs = Vec::new();
+
+    let real = format!("res/day_{}.txt", day.day);
+    if let Ok(text) = fs::read_to_string(&real) {
+        inputs.push(Input {
+            name: real,
+            text,
+            real: true,
+        });
+    }
+
+    let mut examples: Vec<PathBuf> = fs::read_dir("res/examples")
+        .into_iter()
+        .flatten()
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let stem = path
+                .file_stem()
+                .and_then(|stem| stem.to_str())
+                .unwrap_or("");
+            let prefix = format!("day_{}", day.day);
+            stem == prefix || stem.starts_with(&format!("{prefix}_"))
+        })
+        .collect();
+    examples.sort();
+    for path in examples {
+        if let Ok(text) = fs::read_to_string(&path) {
+            inputs.push(Input {
+                name: path.display().to_string(),
+                text,
+                real: false,
+            });
+        }
+    }
+
+    if let Some(generator) = generate::generator(day.day) {
+        let size = options.size.unwrap_or(generator.default_size);
+        for seed in 0..options.random {
+            inputs.push(Input {
+                name: format!("generated (seed {seed})"),
+                text: (generator.generate)(&mut Rng::new(seed), size),
+                real: false,
+            });
+        }
+    }
+
+    inputs
+}
+
+fn describe(outcome: Result<&str, &str>) -> String {
+    match outcome {
+        Ok(answer) => answer.to_string(),
+        Err(error) => format!("failed ({error})"),
+    }
+}
+
+// Runs every implementation of `day` over each input, reporting disagreements with a minimised
+// input, then times them side by side. Returns whether they all agreed.
+pub fn diff(day: &Day, options: &Options) -> bool {
+    let missing: Vec<PathBuf> = day
+        .implementations
+        .iter()
+        .map(|implementation| binary(implementation.bin))
+        .filter(|path| !path.exists())
+        .collect();
+    if !missing.is_empty() {
+        for path in missing {
+            eprintln!("Missing solver {}", path.display());
+        }
+        eprintln!("Build every solver first with `cargo build --release --bins`");
+        process::exit(1);
+    }
+
+    let scratch = Scratch::new();
+    let run_all = |text: &str, real: bool| -> Vec<Option<Outcome>> {
+        let path = scratch.write(text);
+        day.implementations
+            .iter()
+            .map(|implementation| {
+                let skip = real && implementation.exhaustive;
+                (!skip).then(|| run(implementation, path).0)
+            })
+            .collect()
+    };
+
+    let inputs = inputs(day, options);
+    let mut agreed = true;
+    for Input { name, text, real } in &inputs {
+        let outcomes = run_all(text, *real);
+
+        let disagreeing = disagreements(day, &outcomes);
+        if disagreeing.is_empty() {
+            let answers: Vec<String> = parts(day)
+                .into_iter()
+                .filter_map(|part| {
+                    let (_, outcome) = covering(day, &outcomes, part).next()?;
+                    Some(format!("part {part}: {}", describe(answer(outcome, part))))
+                })
+                .collect();
+            println!("{name}: agree ({})", answers.join(", "));
+            continue;
+        }
+
+        agreed = false;
+        for part in disagreeing {
+            println!("{name}: DISAGREE on part {part}");
+            for (implementation, outcome) in covering(day, &outcomes, part) {
+                println!(
+                    "  {}: {}",
+                    implementation.name(),
+                    describe(answer(outcome, part))
+                );
+            }
+
+            let minimized = minimize(text, |candidate| {
+                disagreements(day, &run_all(candidate, *real)).contains(&part)
+            });
+            let outcomes = run_all(&minimized, *real);
+            println!(
+                "  minimised input ({} lines):",
+                minimized.split('\n').count()
+            );
+            for line in minimized.split('\n') {
+                println!("    {line}");
+            }
+            for (implementation, outcome) in covering(day, &outcomes, part) {
+                println!(
+                    "  {}: {}",
+                    implementation.name(),
+                    describe(answer(outcome, part))
+                );
+            }
+        }
+    }
+
+    // Time every implementation on the first input, which is the real puzzle input when present
+    if let Some(input) = inputs.first() {
+        let path = scratch.write(&input.text);
+        println!();
+        println!("Benchmark on {} ({} runs):", input.name, options.runs);
+        for implementation in day.implementations {
+            if input.real && implementation.exhaustive {
+                println!("  {:<24} skipped", implementation.name());
+                continue;
+            }
+            let runs: Vec<Duration> = (0..options.runs)
+                .map(|_| run(implementation, path).1)
+                .collect();
+            let fastest = runs.iter().min().copied().unwrap_or_default();
+            let mean = runs.iter().sum::<Duration>() / options.runs.max(1);
+            println!(
+                "  {:<24} min {:>10.3?}  mean {:>10.3?}",
+                implementation.name(),
+                fastest,
+                mean
+            );
+        }
+    }
+
+    agreed
+}
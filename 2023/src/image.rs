@@ -0,0 +1,204 @@
+use std::{
+    fmt::Write as _,
+    fs::{self, File},
+    io::{self, BufWriter},
+    path::{Path, PathBuf},
+    process,
+};
+
+use crate::args;
+
+pub type Rgb = [u8; 3];
+
+pub const BLACK: Rgb = [0x00, 0x00, 0x00];
+pub const WHITE: Rgb = [0xFF, 0xFF, 0xFF];
+pub const GREY: Rgb = [0x80, 0x80, 0x80];
+pub const RED: Rgb = [0xD0, 0x30, 0x30];
+pub const YELLOW: Rgb = [0xF0, 0xC0, 0x30];
+pub const BLUE: Rgb = [0x30, 0x60, 0xD0];
+
+// Size in pixels of a single grid cell in PNG output
+const CELL_SIZE: usize = 4;
+
+// The output file requested with `--render`, if any
+pub fn render_path() -> Option<PathBuf> {
+    args::value("render").map(PathBuf::from)
+}
+
+// The `--render` path for images that only come as SVG, rejecting other extensions up front
+pub fn svg_render_path() -> Option<PathBuf> {
+    let path = render_path()?;
+    if extension(&path) != "svg" {
+        fail(&path, unsupported("an .svg"));
+    }
+    Some(path)
+}
+
+// Reports an image that couldn't be written and exits
+pub fn fail(path: &Path, error: io::Error) -> ! {
+    eprintln!("Failed to write {}: {error}", path.display());
+    process::exit(1);
+}
+
+fn extension(path: &Path) -> String {
+    path.extension()
+        .map(|extension| extension.to_string_lossy().to_ascii_lowercase())
+        .unwrap_or_default()
+}
+
+// Writes a grid of cells, coloured by `palette(x, y, cell)`. The format follows the file
+// extension: `.png` draws each cell as a square of pixels, `.svg` as a rectangle.
+pub fn write_grid<T>(
+    path: &Path,
+    grid: &[Vec<T>],
+    palette: impl Fn(usize, usize, &T) -> Rgb,
+) -> io::Result<()> {
+    match extension(path).as_str() {
+        "png" => write_grid_png(path, grid, palette),
+        "svg" => {
+            let mut svg = Svg::new();
+            for (y, row) in grid.iter().enumerate() {
+                for (x, cell) in row.iter().enumerate() {
+                    svg.rect(x as i64, y as i64, 1, 1, palette(x, y, cell));
+                }
+            }
+            svg.write(path)
+        }
+        _ => Err(unsupported("a .png or .svg")),
+    }
+}
+
+fn write_grid_png<T>(
+    path: &Path,
+    grid: &[Vec<T>],
+    palette: impl Fn(usize, usize, &T) -> Rgb,
+) -> io::Result<()> {
+    let height = grid.len();
+    let width = grid.iter().map(Vec::len).max().unwrap_or(0);
+    let row_bytes = width * CELL_SIZE * 3;
+
+    let mut pixels = vec![0u8; row_bytes * height * CELL_SIZE];
+    for (y, row) in grid.iter().enumerate() {
+        for (x, cell) in row.iter().enumerate() {
+            let colour = palette(x, y, cell);
+            for dy in 0..CELL_SIZE {
+                let start = (y * CELL_SIZE + dy) * row_bytes + x * CELL_SIZE * 3;
+                for pixel in pixels[start..start + CELL_SIZE * 3].chunks_exact_mut(3) {
+                    pixel.copy_from_slice(&colour);
+                }
+            }
+        }
+    }
+
+    let mut encoder = png::Encoder::new(
+        BufWriter::new(File::create(path)?),
+        (width * CELL_SIZE) as u32,
+        (height * CELL_SIZE) as u32,
+    );
+    encoder.set_color(png::ColorType::Rgb);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(&pixels))
+        .map_err(io::Error::other)
+}
+
+fn unsupported(expected: &str) -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidInput,
+        format!("unsupported image format, expected {expected} file"),
+    )
+}
+
+fn hex(colour: Rgb) -> String {
+    format!("#{:02x}{:02x}{:02x}", colour[0], colour[1], colour[2])
+}
+
+// A minimal SVG document in puzzle coordinates. The view box grows to fit whatever is drawn,
+// so polygons with coordinates far too large for a terminal or bitmap still come out whole.
+pub struct Svg {
+    elements: Vec<String>,
+    min: (i64, i64),
+    max: (i64, i64),
+}
+
+impl Svg {
+    pub fn new() -> Self {
+        Self {
+            elements: Vec::new(),
+            min: (i64::MAX, i64::MAX),
+            max: (i64::MIN, i64::MIN),
+        }
+    }
+
+    fn include(&mut self, (x, y): (i64, i64)) {
+        self.min = (self.min.0.min(x), self.min.1.min(y));
+        self.max = (self.max.0.max(x), self.max.1.max(y));
+    }
+
+    pub fn rect(&mut self, x: i64, y: i64, width: i64, height: i64, fill: Rgb) {
+        self.include((x, y));
+        self.include((x + width, y + height));
+        self.elements.push(format!(
+            r#"<rect x="{x}" y="{y}" width="{width}" height="{height}" fill="{}"/>"#,
+            hex(fill)
+        ));
+    }
+
+    // A closed shape through `vertices`
+    pub fn polygon(&mut self, vertices: &[(i64, i64)], fill: Rgb, stroke: Rgb) {
+        let points = self.points(vertices);
+        self.elements.push(format!(
+            r#"<polygon points="{points}" fill="{}" stroke="{}" stroke-width="1" vector-effect="non-scaling-stroke"/>"#,
+            hex(fill),
+            hex(stroke)
+        ));
+    }
+
+    fn points(&mut self, vertices: &[(i64, i64)]) -> String {
+        let mut points = String::new();
+        for &vertex in vertices {
+            self.include(vertex);
+            if !points.is_empty() {
+                points.push(' ');
+            }
+            write!(points, "{},{}", vertex.0, vertex.1).unwrap();
+        }
+        points
+    }
+
+    pub fn to_svg(&self) -> String {
+        let (min, max) = if self.elements.is_empty() {
+            ((0, 0), (0, 0))
+        } else {
+            (self.min, self.max)
+        };
+        let width = (max.0 - min.0).max(1);
+        let height = (max.1 - min.1).max(1);
+
+        let mut output = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="{} {} {width} {height}" shape-rendering="crispEdges">"#,
+            min.0, min.1
+        );
+        output.push('\n');
+        for element in &self.elements {
+            output.push_str(element);
+            output.push('\n');
+        }
+        output.push_str("</svg>\n");
+        output
+    }
+
+    pub fn write(&self, path: &Path) -> io::Result<()> {
+        if extension(path) != "svg" {
+            return Err(unsupported("an .svg"));
+        }
+        fs::write(path, self.to_svg())
+    }
+}
+
+impl Default for Svg {
+    fn default() -> Self {
+        Self::new()
+    }
+}
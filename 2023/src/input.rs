@@ -0,0 +1,9 @@
+use std::fs;
+
+use crate::args;
+
+// Reads the file given with `--input`, falling back to the day's puzzle input in `res/`
+pub fn read(day: u32) -> String {
+    let path = args::value("input").unwrap_or_else(|| format!("res/day_{day}.txt"));
+    fs::read_to_string(&path).unwrap_or_else(|error| panic!("Failed to read {path}: {error}"))
+}
@@ -0,0 +1,12 @@
+pub mod aho_corasick;
+pub mod args;
+pub mod camel_cards;
+pub mod checked;
+pub mod digit_words;
+pub mod generate;
+pub mod harness;
+pub mod image;
+pub mod input;
+pub mod parallel;
+pub mod render;
+pub mod rng;
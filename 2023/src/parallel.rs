@@ -0,0 +1,82 @@
+use std::{
+    env,
+    num::NonZeroUsize,
+    sync::atomic::{AtomicUsize, Ordering},
+    thread,
+};
+
+use crate::args;
+
+// Each worker gets roughly this many chunks, so uneven items still balance out
+const CHUNKS_PER_THREAD: usize = 4;
+
+// Thread count from `--threads`, then `AOC_THREADS`, then the number of available cores
+pub fn thread_count() -> usize {
+    let threads = args::value("threads").or_else(|| env::var("AOC_THREADS").ok());
+    match threads {
+        Some(threads) => match threads.parse::<usize>() {
+            Ok(threads) if threads > 0 => threads,
+            _ => panic!("Invalid thread count: {threads}"),
+        },
+        None => thread::available_parallelism().map_or(1, NonZeroUsize::get),
+    }
+}
+
+// Maps every item on a scoped pool of `thread_count()` workers. Items are handed out in
+// chunks and the results come back in input order, regardless of which worker ran them.
+pub fn map<T, R, F>(items: &[T], f: F) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+{
+    let threads = thread_count().min(items.len());
+    if threads <= 1 {
+        return items.iter().map(f).collect();
+    }
+
+    let chunk_size = items.len().div_ceil(threads * CHUNKS_PER_THREAD);
+    let chunks: Vec<&[T]> = items.chunks(chunk_size).collect();
+    let next_chunk = AtomicUsize::new(0);
+
+    let mut finished: Vec<(usize, Vec<R>)> = thread::scope(|scope| {
+        let workers: Vec<_> = (0..threads)
+            .map(|_| {
+                scope.spawn(|| {
+                    let mut finished = Vec::new();
+                    loop {
+                        let i = next_chunk.fetch_add(1, Ordering::Relaxed);
+                        if i >= chunks.len() {
+                            break;
+                        }
+                        finished.push((i, chunks[i].iter().map(&f).collect()));
+                    }
+                    finished
+                })
+            })
+            .collect();
+
+        workers
+            .into_iter()
+            .flat_map(|worker| worker.join().unwrap())
+            .collect()
+    });
+
+    finished.sort_unstable_by_key(|(i, _)| *i);
+    finished
+        .into_iter()
+        .flat_map(|(_, results)| results)
+        .collect()
+}
+
+// Maps every item in parallel, then folds the results in input order so that the
+// answer doesn't depend on scheduling. Returns `None` for an empty input.
+pub fn map_reduce<T, R, F, G>(items: &[T], f: F, reduce: G) -> Option<R>
+where
+    T: Sync,
+    R: Send,
+    F: Fn(&T) -> R + Sync,
+    G: Fn(R, R) -> R,
+{
+    map(items, f).into_iter().reduce(reduce)
+}
@@ -0,0 +1,193 @@
+use std::{
+    env,
+    fmt::Write as _,
+    io::{self, IsTerminal, Write as _},
+    thread,
+    time::Duration,
+};
+
+use crate::args;
+
+const DEFAULT_DELAY_MS: u64 = 100;
+
+// Whether the solver was asked to draw its grids with `--visualize`
+pub fn enabled() -> bool {
+    args::flag("visualize")
+}
+
+// Only emit escape codes when writing to a terminal, and respect `NO_COLOR`
+fn use_colour() -> bool {
+    io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none()
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Colour {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Colour {
+    fn code(self) -> u8 {
+        match self {
+            Colour::Red => 1,
+            Colour::Green => 2,
+            Colour::Yellow => 3,
+            Colour::Blue => 4,
+            Colour::Magenta => 5,
+            Colour::Cyan => 6,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Style {
+    pub foreground: Option<Colour>,
+    pub background: Option<Colour>,
+}
+
+impl Style {
+    pub const PLAIN: Style = Style {
+        foreground: None,
+        background: None,
+    };
+
+    pub fn foreground(colour: Colour) -> Self {
+        Self {
+            foreground: Some(colour),
+            background: None,
+        }
+    }
+
+    pub fn background(colour: Colour) -> Self {
+        Self {
+            foreground: None,
+            background: Some(colour),
+        }
+    }
+
+    // Layers `other` on top of this style, keeping whatever it doesn't set
+    fn overlay(self, other: Style) -> Self {
+        Self {
+            foreground: other.foreground.or(self.foreground),
+            background: other.background.or(self.background),
+        }
+    }
+
+    fn escape(self) -> Option<String> {
+        let mut codes = Vec::new();
+        if let Some(colour) = self.foreground {
+            codes.push(format!("3{}", colour.code()));
+        }
+        if let Some(colour) = self.background {
+            codes.push(format!("4{}", colour.code()));
+        }
+        if codes.is_empty() {
+            return None;
+        }
+        Some(format!("\x1b[{}m", codes.join(";")))
+    }
+}
+
+type Glyph<'a, T> = Box<dyn Fn(&T) -> (char, Style) + 'a>;
+type Highlight<'a> = (Style, Box<dyn Fn(usize, usize) -> bool + 'a>);
+
+// Draws a grid of cells as text. Each cell maps to a glyph and base style, and highlight
+// layers are applied on top in the order they were added, so later layers win.
+pub struct Renderer<'a, T> {
+    glyph: Glyph<'a, T>,
+    highlights: Vec<Highlight<'a>>,
+    colour: bool,
+}
+
+impl<'a, T> Renderer<'a, T> {
+    pub fn new(glyph: impl Fn(&T) -> char + 'a) -> Self {
+        Self::styled(move |cell| (glyph(cell), Style::PLAIN))
+    }
+
+    pub fn styled(glyph: impl Fn(&T) -> (char, Style) + 'a) -> Self {
+        Self {
+            glyph: Box::new(glyph),
+            highlights: Vec::new(),
+            colour: use_colour(),
+        }
+    }
+
+    // Applies `style` to every `(x, y)` for which `contains` returns true
+    pub fn highlight(mut self, style: Style, contains: impl Fn(usize, usize) -> bool + 'a) -> Self {
+        self.highlights.push((style, Box::new(contains)));
+        self
+    }
+
+    pub fn render(&self, grid: &[Vec<T>]) -> String {
+        let mut output = String::new();
+        for (y, row) in grid.iter().enumerate() {
+            for (x, cell) in row.iter().enumerate() {
+                let (glyph, style) = (self.glyph)(cell);
+                let style = self
+                    .highlights
+                    .iter()
+                    .filter(|(_, contains)| contains(x, y))
+                    .fold(style, |style, (highlight, _)| style.overlay(*highlight));
+
+                match style.escape().filter(|_| self.colour) {
+                    Some(escape) => write!(output, "{escape}{glyph}\x1b[0m").unwrap(),
+                    None => output.push(glyph),
+                }
+            }
+            output.push('\n');
+        }
+        output
+    }
+
+    pub fn print(&self, grid: &[Vec<T>]) {
+        print!("{}", self.render(grid));
+    }
+}
+
+// Plays rendered frames one after another. On a terminal each frame replaces the previous one
+// after `--delay` milliseconds; otherwise the frames are printed one after another as plain text.
+pub struct Animation {
+    delay: Duration,
+    interactive: bool,
+    frames: usize,
+}
+
+impl Animation {
+    pub fn new() -> Self {
+        let delay = args::value("delay").map_or(DEFAULT_DELAY_MS, |delay| {
+            delay.parse().expect("Invalid animation delay")
+        });
+
+        Self {
+            delay: Duration::from_millis(delay),
+            interactive: io::stdout().is_terminal(),
+            frames: 0,
+        }
+    }
+
+    pub fn frame(&mut self, frame: &str) {
+        let mut stdout = io::stdout().lock();
+        if self.interactive {
+            // Move the cursor home and clear the screen before drawing over the last frame
+            write!(stdout, "\x1b[H\x1b[2J{frame}").unwrap();
+            stdout.flush().unwrap();
+            thread::sleep(self.delay);
+        } else {
+            if self.frames > 0 {
+                writeln!(stdout).unwrap();
+            }
+            write!(stdout, "{frame}").unwrap();
+        }
+        self.frames += 1;
+    }
+}
+
+impl Default for Animation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
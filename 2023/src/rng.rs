@@ -0,0 +1,63 @@
+use std::ops::RangeInclusive;
+
+// SplitMix64. Small, fast and fully determined by its seed, so generated inputs are stable
+// across platforms and releases.
+#[derive(Debug, Clone)]
+pub struct Rng {
+    state: u64,
+}
+
+impl Rng {
+    pub fn new(seed: u64) -> Self {
+        Self { state: seed }
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.state = self.state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    // Uniform in `0..bound`
+    pub fn below(&mut self, bound: u64) -> u64 {
+        assert!(bound > 0, "Empty range");
+        ((self.next_u64() as u128 * bound as u128) >> 64) as u64
+    }
+
+    // Uniform in `0..bound`, for bounds that don't fit in 64 bits
+    pub fn below_u128(&mut self, bound: u128) -> u128 {
+        assert!(bound > 0, "Empty range");
+        if let Ok(bound) = u64::try_from(bound) {
+            return self.below(bound) as u128;
+        }
+        // Rejection sampling over the smallest power of two covering the bound
+        let mask = u128::MAX >> bound.leading_zeros();
+        loop {
+            let value = ((self.next_u64() as u128) << 64 | self.next_u64() as u128) & mask;
+            if value < bound {
+                return value;
+            }
+        }
+    }
+
+    pub fn range(&mut self, range: RangeInclusive<usize>) -> usize {
+        let (start, end) = range.into_inner();
+        start + self.below((end - start) as u64 + 1) as usize
+    }
+
+    pub fn chance(&mut self, probability: f64) -> bool {
+        ((self.next_u64() >> 11) as f64 / (1u64 << 53) as f64) < probability
+    }
+
+    pub fn choose<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.below(items.len() as u64) as usize]
+    }
+
+    pub fn shuffle<T>(&mut self, items: &mut [T]) {
+        for i in (1..items.len()).rev() {
+            items.swap(i, self.below(i as u64 + 1) as usize);
+        }
+    }
+}